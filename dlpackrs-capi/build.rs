@@ -0,0 +1,27 @@
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    println!("cargo:rerun-if-changed=build.rs");
+    #[cfg(feature = "interop-tests")]
+    interop::build()?;
+    Ok(())
+}
+
+#[cfg(feature = "interop-tests")]
+mod interop {
+    use std::{env, error::Error, path::PathBuf, result::Result};
+
+    /// Compiles the C side of the interop tests, linked to the tests only.
+    pub fn build() -> Result<(), Box<dyn Error>> {
+        cc::Build::new()
+            .file("tests/c/interop.c")
+            .include("include")
+            .include("../dlpack-sys/csrc/dlpack/include")
+            .cargo_metadata(false)
+            .compile("interop");
+        let out_path = PathBuf::from(env::var("OUT_DIR")?);
+        println!("cargo:rustc-link-arg-tests=-L{}", out_path.display());
+        println!("cargo:rustc-link-arg-tests=-linterop");
+        println!("cargo:rerun-if-changed=tests/c/interop.c");
+        println!("cargo:rerun-if-changed=include/dlpackrs.h");
+        Ok(())
+    }
+}
@@ -0,0 +1,74 @@
+/*
+ * C ABI of dlpackrs for creating and inspecting DLPack tensors backed by Rust allocations.
+ * Every DLManagedTensor returned here is released by calling its deleter, e.g. with dlpackrs_free.
+ */
+#ifndef DLPACKRS_H_
+#define DLPACKRS_H_
+
+#include <stdbool.h>
+#include <stdint.h>
+
+#include <dlpack/dlpack.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+/* Status returned by the functions inspecting a tensor. */
+typedef enum {
+  DLPackrsStatus_Ok = 0,
+  DLPackrsStatus_NullPointer = 1,
+  DLPackrsStatus_UnsupportedDevice = 2,
+  DLPackrsStatus_UnsupportedDataTypeCode = 3,
+  DLPackrsStatus_InvalidDataType = 4,
+  DLPackrsStatus_NegativeNdim = 5,
+  DLPackrsStatus_NullShape = 6,
+  DLPackrsStatus_NegativeShape = 7,
+  DLPackrsStatus_ElementCountOverflow = 8,
+  DLPackrsStatus_ByteSizeOverflow = 9,
+  DLPackrsStatus_NullData = 10,
+  DLPackrsStatus_StridesOutOfBounds = 11,
+  DLPackrsStatus_Misaligned = 12,
+  DLPackrsStatus_StridesMismatch = 13,
+  DLPackrsStatus_DataOutOfBounds = 14,
+  DLPackrsStatus_InvalidReinterpretation = 15,
+} DLPackrsStatus;
+
+/* Summary of a valid tensor filled in by dlpackrs_describe. */
+typedef struct {
+  /* Number of entries. */
+  uint64_t numel;
+  /* Number of bytes spanned by the strides. */
+  uint64_t nbytes;
+  bool is_c_contiguous;
+  bool is_f_contiguous;
+  bool is_compact;
+} DLPackrsTensorInfo;
+
+/*
+ * Allocates a zeroed compact row-major tensor on cpu whose data is aligned to 64 bytes.
+ * Returns NULL if the data type or the shape is invalid, or if the data can not be allocated.
+ */
+DLManagedTensor *dlpackrs_allocate(DLDataType dtype, const int64_t *shape, int32_t ndim);
+
+/*
+ * Wraps the data of a tensor into a managed tensor whose deleter calls release(ctx), if release is
+ * non-null. The shape and strides are copied. Returns NULL if the tensor is invalid, in which case
+ * release is not called.
+ */
+DLManagedTensor *dlpackrs_wrap(const DLTensor *tensor, void (*release)(void *), void *ctx);
+
+/* Checks that the tensor describes a valid DLPack tensor. */
+DLPackrsStatus dlpackrs_validate(const DLTensor *tensor);
+
+/* Validates the tensor and fills in info with its summary. */
+DLPackrsStatus dlpackrs_describe(const DLTensor *tensor, DLPackrsTensorInfo *info);
+
+/* Releases a managed tensor by calling its deleter. Does nothing if tensor is NULL. */
+void dlpackrs_free(DLManagedTensor *tensor);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* DLPACKRS_H_ */
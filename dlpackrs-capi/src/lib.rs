@@ -0,0 +1,244 @@
+//! C ABI for creating and inspecting DLPack tensors backed by Rust allocations.
+//!
+//! The functions are declared in `include/dlpackrs.h`. Every `DLManagedTensor` returned by this
+//! crate is released by calling its deleter, e.g. through [`dlpackrs_free`].
+
+#![allow(clippy::missing_safety_doc)]
+
+use std::{os::raw::c_void, ptr, slice};
+
+use dlpackrs::{
+    errors::ValidationError,
+    ffi::{DLDataType, DLManagedTensor, DLTensor},
+    Device, ManagedTensor, Tensor,
+};
+
+/// Status returned by the functions inspecting a tensor, where each error matches a
+/// [`ValidationError`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(C)]
+pub enum DLPackrsStatus {
+    Ok = 0,
+    NullPointer = 1,
+    UnsupportedDevice = 2,
+    UnsupportedDataTypeCode = 3,
+    InvalidDataType = 4,
+    NegativeNdim = 5,
+    NullShape = 6,
+    NegativeShape = 7,
+    ElementCountOverflow = 8,
+    ByteSizeOverflow = 9,
+    NullData = 10,
+    StridesOutOfBounds = 11,
+    Misaligned = 12,
+    StridesMismatch = 13,
+    DataOutOfBounds = 14,
+    InvalidReinterpretation = 15,
+}
+
+impl From<ValidationError> for DLPackrsStatus {
+    fn from(e: ValidationError) -> Self {
+        match e {
+            ValidationError::UnsupportedDevice(_) => DLPackrsStatus::UnsupportedDevice,
+            ValidationError::UnsupportedDataTypeCode(_) => DLPackrsStatus::UnsupportedDataTypeCode,
+            ValidationError::InvalidDataType(_) => DLPackrsStatus::InvalidDataType,
+            ValidationError::NegativeNdim(_) => DLPackrsStatus::NegativeNdim,
+            ValidationError::NullShape(_) => DLPackrsStatus::NullShape,
+            ValidationError::NegativeShape { .. } => DLPackrsStatus::NegativeShape,
+            ValidationError::ElementCountOverflow => DLPackrsStatus::ElementCountOverflow,
+            ValidationError::ByteSizeOverflow => DLPackrsStatus::ByteSizeOverflow,
+            ValidationError::NullData(_) => DLPackrsStatus::NullData,
+            ValidationError::StridesOutOfBounds => DLPackrsStatus::StridesOutOfBounds,
+            ValidationError::Misaligned(_) => DLPackrsStatus::Misaligned,
+            ValidationError::StridesMismatch { .. } => DLPackrsStatus::StridesMismatch,
+            ValidationError::DataOutOfBounds(_) => DLPackrsStatus::DataOutOfBounds,
+            ValidationError::InvalidReinterpretation { .. } => {
+                DLPackrsStatus::InvalidReinterpretation
+            }
+        }
+    }
+}
+
+/// Summary of a valid tensor filled in by [`dlpackrs_describe`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[repr(C)]
+pub struct DLPackrsTensorInfo {
+    /// Number of entries.
+    pub numel: u64,
+    /// Number of bytes spanned by the strides.
+    pub nbytes: u64,
+    pub is_c_contiguous: bool,
+    pub is_f_contiguous: bool,
+    pub is_compact: bool,
+}
+
+/// Alignment (in bytes) of the data allocated by [`dlpackrs_allocate`].
+const ALIGNMENT: usize = 64;
+
+/// Unit of the data allocated by [`dlpackrs_allocate`], which sets its alignment.
+#[derive(Clone, Copy)]
+#[repr(C, align(64))]
+struct Block([u8; ALIGNMENT]);
+
+/// Context released by the deleter of the tensors created by [`dlpackrs_wrap`].
+struct Release {
+    release: Option<unsafe extern "C" fn(*mut c_void)>,
+    ctx: *mut c_void,
+}
+
+// the producer hands the context over along with the tensor, to be released from any thread
+unsafe impl Send for Release {}
+
+impl Release {
+    unsafe fn call(self) {
+        if let Some(release) = self.release {
+            release(self.ctx);
+        }
+    }
+}
+
+/// Returns the `ndim` entries `ptr` points to, if `ptr` is non-null or `ndim` is zero.
+unsafe fn dims(ptr: *const i64, ndim: i32) -> Option<Box<[i64]>> {
+    match ndim {
+        0 => Some(Box::new([])),
+        n if n > 0 && !ptr.is_null() => Some(slice::from_raw_parts(ptr, n as usize).into()),
+        _ => None,
+    }
+}
+
+/// Returns the compact row-major strides (in number of elements) of the given shape, unless they
+/// overflow.
+fn row_major_strides(shape: &[i64]) -> Option<Box<[i64]>> {
+    let mut strides = vec![1_i64; shape.len()];
+    for i in (0..shape.len().saturating_sub(1)).rev() {
+        strides[i] = strides[i + 1].checked_mul(shape[i + 1].max(1))?;
+    }
+    Some(strides.into_boxed_slice())
+}
+
+/// Allocates a zeroed compact row-major tensor on cpu whose data is aligned to 64 bytes.
+///
+/// Returns null if the data type or the shape is invalid, or if the data can not be allocated.
+#[no_mangle]
+pub unsafe extern "C" fn dlpackrs_allocate(
+    dtype: DLDataType,
+    shape: *const i64,
+    ndim: i32,
+) -> *mut DLManagedTensor {
+    let mut shape = match dims(shape, ndim) {
+        Some(shape) => shape,
+        None => return ptr::null_mut(),
+    };
+    let mut strides = match row_major_strides(&shape) {
+        Some(strides) => strides,
+        None => return ptr::null_mut(),
+    };
+    let mut tensor = Tensor::new(
+        ptr::null_mut(),
+        Device::default(),
+        ndim,
+        dtype.into(),
+        shape.as_mut_ptr(),
+        strides.as_mut_ptr(),
+        0,
+    );
+    let bytes = match (tensor.numel(), tensor.size()) {
+        (Ok(_), Some(bytes)) => bytes,
+        _ => return ptr::null_mut(),
+    };
+    let blocks = bytes.div_ceil(ALIGNMENT);
+    let mut data = Vec::new();
+    if data.try_reserve_exact(blocks).is_err() {
+        return ptr::null_mut();
+    }
+    data.resize(blocks, Block([0; ALIGNMENT]));
+    tensor.as_dltensor_mut().data = data.as_mut_ptr() as *mut c_void;
+    if tensor.validate().is_err() {
+        return ptr::null_mut();
+    }
+    ManagedTensor::<()>::with_deleter(tensor, move |_| drop((data, shape, strides))).into_raw()
+}
+
+/// Wraps the data of a tensor described by a foreign producer into a managed tensor, whose
+/// deleter calls `release(ctx)`, if `release` is non-null. The shape and strides are copied, so
+/// that they need not outlive the call.
+///
+/// Returns null if the tensor is invalid, in which case `release` is not called.
+#[no_mangle]
+pub unsafe extern "C" fn dlpackrs_wrap(
+    tensor: *const DLTensor,
+    release: Option<unsafe extern "C" fn(*mut c_void)>,
+    ctx: *mut c_void,
+) -> *mut DLManagedTensor {
+    if tensor.is_null() {
+        return ptr::null_mut();
+    }
+    let mut dlt = *tensor;
+    let (mut shape, strides) = match dims(dlt.shape, dlt.ndim) {
+        Some(shape) => (shape, dims(dlt.strides, dlt.ndim)),
+        None => return ptr::null_mut(),
+    };
+    let mut strides = match strides.or_else(|| row_major_strides(&shape)) {
+        Some(strides) => strides,
+        None => return ptr::null_mut(),
+    };
+    dlt.shape = shape.as_mut_ptr();
+    dlt.strides = strides.as_mut_ptr();
+    let tensor = Tensor::from_inner(dlt);
+    if tensor.validate().is_err() {
+        return ptr::null_mut();
+    }
+    let release = Release { release, ctx };
+    ManagedTensor::<()>::with_deleter(tensor, move |_| {
+        drop((shape, strides));
+        release.call();
+    })
+    .into_raw()
+}
+
+/// Checks that the tensor describes a valid DLPack tensor.
+#[no_mangle]
+pub unsafe extern "C" fn dlpackrs_validate(tensor: *const DLTensor) -> DLPackrsStatus {
+    if tensor.is_null() {
+        return DLPackrsStatus::NullPointer;
+    }
+    match Tensor::from_inner(*tensor).validate() {
+        Ok(()) => DLPackrsStatus::Ok,
+        Err(e) => e.into(),
+    }
+}
+
+/// Validates the tensor and fills in `info` with its summary.
+#[no_mangle]
+pub unsafe extern "C" fn dlpackrs_describe(
+    tensor: *const DLTensor,
+    info: *mut DLPackrsTensorInfo,
+) -> DLPackrsStatus {
+    if tensor.is_null() || info.is_null() {
+        return DLPackrsStatus::NullPointer;
+    }
+    let tensor = Tensor::from_inner(*tensor);
+    if let Err(e) = tensor.validate() {
+        return e.into();
+    }
+    let numel = match tensor.numel() {
+        Ok(numel) => numel,
+        Err(e) => return e.into(),
+    };
+    *info = DLPackrsTensorInfo {
+        numel: numel as u64,
+        nbytes: tensor.size().unwrap_or(0) as u64,
+        is_c_contiguous: tensor.is_c_contiguous(),
+        is_f_contiguous: tensor.is_f_contiguous(),
+        is_compact: tensor.is_compact(),
+    };
+    DLPackrsStatus::Ok
+}
+
+/// Releases a managed tensor by calling its deleter. Does nothing if `tensor` is null.
+#[no_mangle]
+pub unsafe extern "C" fn dlpackrs_free(tensor: *mut DLManagedTensor) {
+    if !tensor.is_null() {
+        drop(ManagedTensor::<()>::from_raw(tensor));
+    }
+}
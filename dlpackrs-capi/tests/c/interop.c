@@ -0,0 +1,85 @@
+/* C producer and consumer of the interop tests. */
+#include <stdlib.h>
+
+#include "dlpackrs.h"
+
+static int deleted = 0;
+
+int interop_deleted(void) { return deleted; }
+
+static void delete_c_tensor(DLManagedTensor *self) {
+  free(self->dl_tensor.data);
+  free(self->dl_tensor.shape);
+  free(self->dl_tensor.strides);
+  free(self);
+  deleted++;
+}
+
+/* Produces a 2x3 float32 tensor holding 0, 1, ..., 5 in column-major order. */
+DLManagedTensor *interop_produce(void) {
+  DLManagedTensor *mt = malloc(sizeof(DLManagedTensor));
+  float *data = malloc(6 * sizeof(float));
+  int64_t *shape = malloc(2 * sizeof(int64_t));
+  int64_t *strides = malloc(2 * sizeof(int64_t));
+  for (int i = 0; i < 2; i++) {
+    for (int j = 0; j < 3; j++) {
+      data[i + 2 * j] = (float)(3 * i + j);
+    }
+  }
+  shape[0] = 2;
+  shape[1] = 3;
+  strides[0] = 1;
+  strides[1] = 2;
+  mt->dl_tensor.data = data;
+  mt->dl_tensor.device.device_type = kDLCPU;
+  mt->dl_tensor.device.device_id = 0;
+  mt->dl_tensor.ndim = 2;
+  mt->dl_tensor.dtype.code = kDLFloat;
+  mt->dl_tensor.dtype.bits = 32;
+  mt->dl_tensor.dtype.lanes = 1;
+  mt->dl_tensor.shape = shape;
+  mt->dl_tensor.strides = strides;
+  mt->dl_tensor.byte_offset = 0;
+  mt->manager_ctx = NULL;
+  mt->deleter = delete_c_tensor;
+  return mt;
+}
+
+/* Releases a buffer handed over to dlpackrs_wrap. */
+void interop_release(void *ctx) {
+  free(ctx);
+  deleted++;
+}
+
+/* Returns a float32 buffer of the given length holding 0, 1, ..., len - 1. */
+float *interop_buffer(int64_t len) {
+  float *data = malloc(len * sizeof(float));
+  for (int64_t i = 0; i < len; i++) {
+    data[i] = (float)i;
+  }
+  return data;
+}
+
+/*
+ * Consumes a float32 tensor of two dimensions, returning the sum of the entries weighted by their
+ * row-major position, then calls its deleter. Returns -1 for other tensors.
+ */
+double interop_consume(DLManagedTensor *mt) {
+  DLTensor *t = &mt->dl_tensor;
+  double sum = -1;
+  if (t->ndim == 2 && t->dtype.code == kDLFloat && t->dtype.bits == 32 && t->dtype.lanes == 1) {
+    const float *data = (const float *)((const char *)t->data + t->byte_offset);
+    int64_t rstride = t->strides ? t->strides[0] : t->shape[1];
+    int64_t cstride = t->strides ? t->strides[1] : 1;
+    sum = 0;
+    for (int64_t i = 0; i < t->shape[0]; i++) {
+      for (int64_t j = 0; j < t->shape[1]; j++) {
+        sum += (double)(i * t->shape[1] + j) * data[i * rstride + j * cstride];
+      }
+    }
+  }
+  if (mt->deleter) {
+    mt->deleter(mt);
+  }
+  return sum;
+}
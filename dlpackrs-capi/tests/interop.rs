@@ -0,0 +1,108 @@
+use std::{os::raw::c_void, ptr};
+
+use dlpackrs::{ffi::DLManagedTensor, DataType, ManagedTensor};
+use dlpackrs_capi::*;
+
+extern "C" {
+    fn interop_deleted() -> i32;
+    fn interop_produce() -> *mut DLManagedTensor;
+    fn interop_release(ctx: *mut c_void);
+    fn interop_buffer(len: i64) -> *mut f32;
+    fn interop_consume(mt: *mut DLManagedTensor) -> f64;
+}
+
+/// Sum of the squares of 0, 1, ..., 5, i.e. what `interop_consume` returns for the entries 0, 1,
+/// ..., 5 in row-major order.
+const EXPECTED: f64 = 55.;
+
+// the tests run one after another since they count the deletions on the C side
+#[test]
+fn interop() {
+    c_producer_rust_consumer();
+    rust_producer_c_consumer();
+    wrapped_c_buffer();
+    invalid_tensors();
+}
+
+fn c_producer_rust_consumer() {
+    let ptr = unsafe { interop_produce() };
+    let mut info = DLPackrsTensorInfo::default();
+    assert_eq!(
+        unsafe { dlpackrs_describe(&(*ptr).dl_tensor, &mut info) },
+        DLPackrsStatus::Ok
+    );
+    assert_eq!(info.numel, 6);
+    assert_eq!(info.nbytes, 24);
+    assert!(info.is_f_contiguous && !info.is_c_contiguous);
+    let mt = unsafe { ManagedTensor::<f32>::from_raw(ptr) };
+    let t = mt.tensor();
+    let entries = (0..2)
+        .flat_map(|i| (0..3).map(move |j| [i, j]))
+        .map(|index| unsafe {
+            *(t.data() as *const f32).offset(t.element_offset(&index).unwrap() as isize)
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(entries, vec![0., 1., 2., 3., 4., 5.]);
+    drop(mt);
+    assert_eq!(unsafe { interop_deleted() }, 1);
+}
+
+fn rust_producer_c_consumer() {
+    let shape = [2_i64, 3];
+    let ptr = unsafe { dlpackrs_allocate(DataType::f32().into(), shape.as_ptr(), 2) };
+    assert!(!ptr.is_null());
+    assert_eq!(unsafe { (*ptr).dl_tensor.data as usize % 64 }, 0);
+    let mut mt = unsafe { ManagedTensor::<f32>::from_raw(ptr) };
+    let entries = mt.tensor_mut().into_slice_mut::<f32>().unwrap();
+    assert_eq!(entries, &[0.; 6]);
+    entries.copy_from_slice(&[0., 1., 2., 3., 4., 5.]);
+    assert_eq!(unsafe { interop_consume(mt.into_raw()) }, EXPECTED);
+}
+
+fn wrapped_c_buffer() {
+    let mut shape = [3_i64, 2];
+    let mut strides = [1_i64, 3];
+    let mt = ManagedTensor::<f32>::from_vec(vec![0.; 6], &[3, 2]).unwrap();
+    let mut dl_tensor = *mt.tensor().as_dltensor();
+    dl_tensor.data = unsafe { interop_buffer(6) } as *mut c_void;
+    dl_tensor.shape = shape.as_mut_ptr();
+    dl_tensor.strides = strides.as_mut_ptr();
+    let ptr = unsafe { dlpackrs_wrap(&dl_tensor, Some(interop_release), dl_tensor.data) };
+    assert!(!ptr.is_null());
+    // the transpose of the column-major 3x2 buffer holds 0, 1, ..., 5 in row-major order
+    unsafe {
+        let t = &mut (*ptr).dl_tensor;
+        ptr::swap(t.shape, t.shape.add(1));
+        ptr::swap(t.strides, t.strides.add(1));
+    }
+    assert_eq!(unsafe { interop_consume(ptr) }, EXPECTED);
+    assert_eq!(unsafe { interop_deleted() }, 2);
+}
+
+fn invalid_tensors() {
+    assert_eq!(
+        unsafe { dlpackrs_validate(ptr::null()) },
+        DLPackrsStatus::NullPointer
+    );
+    let shape = [2_i64, -1];
+    let ptr = unsafe { dlpackrs_allocate(DataType::f32().into(), shape.as_ptr(), 2) };
+    assert!(ptr.is_null());
+    let shape = [2_i64];
+    let ptr = unsafe { dlpackrs_allocate(DataType::new(42, 32, 1).into(), shape.as_ptr(), 1) };
+    assert!(ptr.is_null());
+    // 4 EiB of data can not be allocated
+    let shape = [1_i64 << 40, 1 << 20];
+    let ptr = unsafe { dlpackrs_allocate(DataType::f32().into(), shape.as_ptr(), 2) };
+    assert!(ptr.is_null());
+    let mt = ManagedTensor::<f32>::from_vec(vec![0.; 2], &[2]).unwrap();
+    let mut dl_tensor = *mt.tensor().as_dltensor();
+    dl_tensor.data = ptr::null_mut();
+    assert_eq!(
+        unsafe { dlpackrs_validate(&dl_tensor) },
+        DLPackrsStatus::NullData
+    );
+    let ptr = unsafe { dlpackrs_wrap(&dl_tensor, Some(interop_release), ptr::null_mut()) };
+    assert!(ptr.is_null());
+    assert_eq!(unsafe { interop_deleted() }, 2);
+    unsafe { dlpackrs_free(ptr::null_mut()) };
+}
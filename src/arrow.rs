@@ -0,0 +1,378 @@
+//! Zero-copy conversions between [Apache Arrow](https://docs.rs/arrow) arrays and tensors.
+//!
+//! A `FixedSizeListArray` of primitives is a batched tensor whose first dimension is the length of
+//! the array, e.g. the storage of the
+//! [`arrow.fixed_shape_tensor`](https://arrow.apache.org/docs/format/CanonicalExtensions.html#fixed-shape-tensor)
+//! extension type, whose elements are laid out in row-major order. Arrow buffers are immutable,
+//! hence they are exported as read-only tensors.
+//!
+//! ## Example
+//!
+//! ```
+//! use std::sync::Arc;
+//! use arrow_array::{Array, FixedSizeListArray, Float32Array};
+//! use arrow_schema::{DataType, Field};
+//! use dlpackrs::{ManagedTensor, ManagedTensorVersioned};
+//!
+//! let values = Float32Array::from(vec![1., 2., 3., 4., 5., 6., 7., 8., 9., 10., 11., 12.]);
+//! let field = Arc::new(Field::new("item", DataType::Float32, false));
+//! let array = FixedSizeListArray::new(field, 6, Arc::new(values), None);
+//! // two tensors of shape [2, 3]
+//! let mt = ManagedTensorVersioned::<f32>::from_fixed_shape_tensor(&array, &[2, 3]).unwrap();
+//! assert_eq!(mt.tensor().shape().unwrap(), vec![2, 2, 3]);
+//!
+//! let mt = ManagedTensor::from_vec(vec![1f32, 2., 3., 4.], &[2, 2]).unwrap();
+//! let array = mt.into_arrow().unwrap();
+//! assert_eq!(array.len(), 2);
+//! ```
+
+use std::{
+    convert::TryFrom, mem, os::raw::c_void, panic::AssertUnwindSafe, ptr::NonNull, sync::Arc,
+};
+
+use arrow_array::{
+    make_array, Array, ArrayRef, ArrowPrimitiveType, FixedSizeListArray, PrimitiveArray,
+};
+use arrow_buffer::Buffer;
+use arrow_data::ArrayData;
+use arrow_schema::{DataType as ArrowDataType, Field};
+
+use crate::{
+    datatype::{DataType, Element},
+    device::Device,
+    errors::{ArrowConversionError, TensorAccessError},
+    tensor::{row_major_strides, ManagedTensor, ManagedTensorVersioned, Tensor, TensorFlags},
+};
+
+/// Returns the pairs of tensor and arrow data types describing the same primitives.
+fn dtypes() -> [(DataType, ArrowDataType); 11] {
+    [
+        (DataType::i8(), ArrowDataType::Int8),
+        (DataType::i16(), ArrowDataType::Int16),
+        (DataType::i32(), ArrowDataType::Int32),
+        (DataType::i64(), ArrowDataType::Int64),
+        (DataType::u8(), ArrowDataType::UInt8),
+        (DataType::u16(), ArrowDataType::UInt16),
+        (DataType::u32(), ArrowDataType::UInt32),
+        (DataType::u64(), ArrowDataType::UInt64),
+        (DataType::f16(), ArrowDataType::Float16),
+        (DataType::f32(), ArrowDataType::Float32),
+        (DataType::f64(), ArrowDataType::Float64),
+    ]
+}
+
+/// Returns the values buffer of a primitive array of `T` without nulls, along with the pointer to
+/// its first entry and the number of entries.
+fn primitive_values<T: Element>(
+    array: &dyn Array,
+) -> Result<(Buffer, *mut c_void, usize), ArrowConversionError> {
+    let found = dtypes()
+        .into_iter()
+        .find(|(_, arrow)| arrow == array.data_type())
+        .map(|(dtype, _)| dtype)
+        .ok_or_else(|| ArrowConversionError::UnsupportedDataType(array.data_type().to_string()))?;
+    if found != T::DTYPE {
+        return Err(TensorAccessError::DataTypeMismatch {
+            expected: T::DTYPE,
+            found,
+        }
+        .into());
+    }
+    if array.null_count() > 0 {
+        return Err(ArrowConversionError::Nulls(array.null_count()));
+    }
+    let data = array.to_data();
+    let buffer = data.buffers()[0].clone();
+    let ptr = buffer
+        .as_ptr()
+        .wrapping_add(data.offset() * mem::size_of::<T>()) as *mut c_void;
+    Ok((buffer, ptr, data.len()))
+}
+
+/// Returns the values buffer of a fixed size list array of `T` without nulls, along with the
+/// pointer to its first entry, checking that the lists hold tensors of the given shape.
+fn fixed_size_list_values<T: Element>(
+    array: &FixedSizeListArray,
+    shape: &[usize],
+) -> Result<(Buffer, *mut c_void), ArrowConversionError> {
+    if array.null_count() > 0 {
+        return Err(ArrowConversionError::Nulls(array.null_count()));
+    }
+    let len = array.value_length() as usize;
+    let numel = shape
+        .iter()
+        .try_fold(1_usize, |acc, &size| acc.checked_mul(size));
+    if numel != Some(len) {
+        return Err(ArrowConversionError::ShapeMismatch {
+            shape: shape.to_vec(),
+            len,
+        });
+    }
+    let (buffer, ptr, _) = primitive_values::<T>(array.values().as_ref())?;
+    Ok((buffer, ptr))
+}
+
+/// Exports the values of a primitive array without nulls as a one dimensional read-only tensor on
+/// cpu, which keeps the arrow buffer alive.
+impl<A> TryFrom<&PrimitiveArray<A>> for ManagedTensorVersioned<'static, A::Native>
+where
+    A: ArrowPrimitiveType,
+    A::Native: Element,
+{
+    type Error = ArrowConversionError;
+
+    fn try_from(array: &PrimitiveArray<A>) -> Result<Self, Self::Error> {
+        let (buffer, ptr, len) = primitive_values::<A::Native>(array)?;
+        let shape = vec![len as i64];
+        let strides = row_major_strides(&shape).map_err(TensorAccessError::from)?;
+        Ok(ManagedTensorVersioned::from_any_owner(
+            buffer,
+            ptr,
+            Device::default(),
+            A::Native::DTYPE,
+            shape,
+            strides,
+            0,
+        )
+        .with_flags(TensorFlags::READ_ONLY))
+    }
+}
+
+/// Exports a fixed size list array of primitives without nulls as a read-only tensor on cpu of
+/// shape `[len, value_length]`, which keeps the arrow buffer alive.
+impl<T: Element> TryFrom<&FixedSizeListArray> for ManagedTensorVersioned<'static, T> {
+    type Error = ArrowConversionError;
+
+    fn try_from(array: &FixedSizeListArray) -> Result<Self, Self::Error> {
+        Self::from_fixed_shape_tensor(array, &[array.value_length() as usize])
+    }
+}
+
+impl<T: Element> ManagedTensorVersioned<'static, T> {
+    /// Exports the storage of an `arrow.fixed_shape_tensor` array, i.e. a fixed size list array of
+    /// primitives without nulls, as a read-only tensor on cpu of shape `[len, shape...]`, where
+    /// `shape` is the shape of the elements. The tensor keeps the arrow buffer alive.
+    pub fn from_fixed_shape_tensor(
+        array: &FixedSizeListArray,
+        shape: &[usize],
+    ) -> Result<Self, ArrowConversionError> {
+        let (buffer, ptr) = fixed_size_list_values::<T>(array, shape)?;
+        let shape = std::iter::once(array.len())
+            .chain(shape.iter().copied())
+            .map(|size| size as i64)
+            .collect::<Vec<_>>();
+        let strides = row_major_strides(&shape).map_err(TensorAccessError::from)?;
+        Ok(ManagedTensorVersioned::from_any_owner(
+            buffer,
+            ptr,
+            Device::default(),
+            T::DTYPE,
+            shape,
+            strides,
+            0,
+        )
+        .with_flags(TensorFlags::READ_ONLY))
+    }
+}
+
+/// Keeps the owner of the entries of a tensor alive as the allocation of an arrow buffer, which is
+/// `Send` and `Sync` if the owner is.
+struct TensorAllocation<M>(AssertUnwindSafe<M>);
+
+/// The first entry, shape and arrow data type of a compact row-major tensor on the host.
+type ArrowLayout = (*mut c_void, Vec<i64>, ArrowDataType);
+
+/// Returns the layout of a tensor of `T` which can be moved into an arrow array.
+fn arrow_layout<T: Element>(tensor: &Tensor<'_>) -> Result<ArrowLayout, ArrowConversionError> {
+    let (ptr, shape, _) = tensor.checked_entries::<T>()?;
+    if !tensor.is_c_contiguous() {
+        return Err(TensorAccessError::NotContiguous.into());
+    }
+    let dtype = dtypes()
+        .into_iter()
+        .find(|(dtype, _)| *dtype == T::DTYPE)
+        .map(|(_, arrow)| arrow)
+        .ok_or_else(|| ArrowConversionError::UnsupportedDataType(T::DTYPE.to_string()))?;
+    Ok((ptr as *mut c_void, shape.to_vec(), dtype))
+}
+
+/// Returns the arrow array holding the entries of a tensor of `T` with the given layout, which
+/// `owner` keeps alive, without copying them. Tensors of one dimension (or none) are primitive
+/// arrays and the others are fixed size list arrays whose lists hold the entries of the first
+/// dimension.
+fn into_arrow<T: Element, M: Send + Sync + 'static>(
+    (ptr, shape, dtype): ArrowLayout,
+    owner: M,
+) -> Result<ArrayRef, ArrowConversionError> {
+    let numel = shape.iter().product::<i64>() as usize;
+    let buffer = unsafe {
+        Buffer::from_custom_allocation(
+            NonNull::new_unchecked(ptr as *mut u8),
+            numel * mem::size_of::<T>(),
+            Arc::new(TensorAllocation(AssertUnwindSafe(owner))),
+        )
+    };
+    let values = make_array(
+        ArrayData::builder(dtype.clone())
+            .len(numel)
+            .add_buffer(buffer)
+            .build()?,
+    );
+    if shape.len() <= 1 {
+        return Ok(values);
+    }
+    let len = shape[1..].iter().product::<i64>();
+    let value_length = i32::try_from(len).map_err(|_| {
+        ArrowConversionError::ValueLengthOverflow(
+            shape[1..].iter().map(|&size| size as usize).collect(),
+        )
+    })?;
+    let field = Arc::new(Field::new("item", dtype, false));
+    let array = FixedSizeListArray::try_new_with_length(
+        field,
+        value_length,
+        values,
+        None,
+        shape[0] as usize,
+    )?;
+    Ok(Arc::new(array))
+}
+
+impl<T: Element + Send + Sync> ManagedTensor<'static, T> {
+    /// Moves a compact row-major tensor on the host into an arrow array without copying its
+    /// entries, which are released along with the arrow buffer. Tensors of one dimension become
+    /// primitive arrays and the others fixed size list arrays of the entries of their first
+    /// dimension, i.e. the storage of an `arrow.fixed_shape_tensor` array.
+    ///
+    /// Only the tensors created by [`ManagedTensor::from_vec`] or from arrow arrays are moved,
+    /// since arrow may release its buffers from any thread while the owners of other tensors, e.g.
+    /// foreign ones, may have to be released from the thread they have been created on. Fails
+    /// with [`ArrowConversionError::UnsupportedOwner`] otherwise.
+    pub fn into_arrow(self) -> Result<ArrayRef, ArrowConversionError> {
+        let layout = arrow_layout::<T>(self.tensor())?;
+        match self.into_owner::<Vec<T>>() {
+            Ok(data) => into_arrow::<T, _>(layout, data),
+            Err(mt) => match mt.into_owner::<Buffer>() {
+                Ok(buffer) => into_arrow::<T, _>(layout, buffer),
+                Err(_) => Err(ArrowConversionError::UnsupportedOwner),
+            },
+        }
+    }
+}
+
+impl<T: Element + Send + Sync> ManagedTensorVersioned<'static, T> {
+    /// Same as [`ManagedTensor::into_arrow`] for the versioned layout.
+    pub fn into_arrow(self) -> Result<ArrayRef, ArrowConversionError> {
+        let layout = arrow_layout::<T>(self.tensor())?;
+        match self.into_owner::<Vec<T>>() {
+            Ok(data) => into_arrow::<T, _>(layout, data),
+            Err(mt) => match mt.into_owner::<Buffer>() {
+                Ok(buffer) => into_arrow::<T, _>(layout, buffer),
+                Err(_) => Err(ArrowConversionError::UnsupportedOwner),
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow_array::{Float64Array, Int32Array};
+
+    #[test]
+    fn fixed_size_list() {
+        let values = Int32Array::from((0..12).collect::<Vec<_>>());
+        let field = Arc::new(Field::new("item", ArrowDataType::Int32, false));
+        let array = FixedSizeListArray::new(field, 4, Arc::new(values), None).slice(1, 2);
+        let mt = ManagedTensorVersioned::<i32>::try_from(&array).unwrap();
+        assert!(mt.is_read_only());
+        assert_eq!(mt.tensor().shape().unwrap(), vec![2, 4]);
+        assert_eq!(
+            mt.tensor().as_slice::<i32>().unwrap(),
+            &(4..12).collect::<Vec<_>>()[..]
+        );
+        assert!(matches!(
+            ManagedTensorVersioned::<i32>::from_fixed_shape_tensor(&array, &[3, 2]),
+            Err(ArrowConversionError::ShapeMismatch { .. })
+        ));
+        assert!(matches!(
+            ManagedTensorVersioned::<i32>::from_fixed_shape_tensor(&array, &[usize::MAX, 2]),
+            Err(ArrowConversionError::ShapeMismatch { .. })
+        ));
+        assert!(matches!(
+            ManagedTensorVersioned::<f32>::try_from(&array),
+            Err(ArrowConversionError::Access(
+                TensorAccessError::DataTypeMismatch { .. }
+            ))
+        ));
+
+        let mt = ManagedTensorVersioned::<i32>::from_fixed_shape_tensor(&array, &[2, 2]).unwrap();
+        let ptr = mt.tensor().data();
+        let array = mt.into_arrow().unwrap();
+        let array = array.as_any().downcast_ref::<FixedSizeListArray>().unwrap();
+        assert_eq!((array.len(), array.value_length()), (2, 4));
+        let values = array
+            .values()
+            .as_any()
+            .downcast_ref::<Int32Array>()
+            .unwrap();
+        assert_eq!(values.values().as_ptr() as *mut c_void, ptr);
+
+        let mt = ManagedTensor::<u8>::from_vec(vec![], &[0, 1 << 31]).unwrap();
+        assert!(matches!(
+            mt.into_arrow(),
+            Err(ArrowConversionError::ValueLengthOverflow(_))
+        ));
+    }
+
+    #[test]
+    fn primitive_array() {
+        let array = Float64Array::from(vec![Some(1.), None]);
+        assert!(matches!(
+            ManagedTensorVersioned::try_from(&array),
+            Err(ArrowConversionError::Nulls(1))
+        ));
+        let array = Float64Array::from(vec![1., 2., 3.]).slice(1, 2);
+        let mt = ManagedTensorVersioned::try_from(&array).unwrap();
+        assert_eq!(mt.tensor().as_slice::<f64>().unwrap(), &[2., 3.]);
+
+        let mt = ManagedTensor::from_vec(vec![1f64, 2., 3.], &[3]).unwrap();
+        let array = mt.into_arrow().unwrap();
+        let array = array.as_any().downcast_ref::<Float64Array>().unwrap();
+        assert_eq!(array.values(), &[1., 2., 3.]);
+
+        let mut data = vec![1f64, 2., 3., 4.];
+        let ptr = data.as_mut_ptr() as *mut c_void;
+        let mt = ManagedTensor::<f64>::from_owner(
+            data,
+            ptr,
+            Device::default(),
+            DataType::f64(),
+            vec![2, 2],
+            vec![1, 2],
+            0,
+        );
+        assert!(matches!(
+            mt.into_arrow(),
+            Err(ArrowConversionError::Access(
+                TensorAccessError::NotContiguous
+            ))
+        ));
+
+        let mut data = vec![1f64, 2.];
+        let ptr = data.as_mut_ptr() as *mut c_void;
+        let mt = ManagedTensor::<f64>::from_owner(
+            std::rc::Rc::new(data),
+            ptr,
+            Device::default(),
+            DataType::f64(),
+            vec![2],
+            vec![1],
+            0,
+        );
+        assert!(matches!(
+            mt.into_arrow(),
+            Err(ArrowConversionError::UnsupportedOwner)
+        ));
+    }
+}
@@ -0,0 +1,258 @@
+//! Conversions between [candle](https://docs.rs/candle-core) tensors on cpu and tensors.
+//!
+//! A candle tensor is exported as a read-only tensor without copying, since candle tensors share
+//! their storage, keeping the storage alive, and is taken back as is from the
+//! ManagedTensorVersioned it has been exported to. Any other tensor is copied into a new
+//! contiguous candle tensor, since candle tensors own their storage.
+//!
+//! ## Example
+//!
+//! ```
+//! use std::convert::TryFrom;
+//! use candle_core::{Device, Tensor};
+//! use dlpackrs::{DataType, ManagedTensorVersioned};
+//!
+//! let t = Tensor::arange(0f32, 6., &Device::Cpu).unwrap().reshape((2, 3)).unwrap().t().unwrap();
+//! let mt = ManagedTensorVersioned::<()>::try_from(&t).unwrap();
+//! assert!(mt.is_read_only());
+//! assert_eq!(mt.tensor().dtype(), DataType::f32());
+//! assert_eq!(mt.tensor().strides(), Some(vec![1, 3]));
+//! let t = mt.into_candle().unwrap();
+//! assert_eq!(t.dims(), &[3, 2]);
+//! ```
+
+use std::{convert::TryFrom, os::raw::c_void, slice};
+
+use candle_core::{CpuStorage, DType, Device as CandleDevice, Storage};
+
+use crate::{
+    datatype::DataType,
+    device::Device,
+    errors::{CandleConversionError, TensorAccessError},
+    tensor::{all_offsets, ManagedTensor, ManagedTensorVersioned, Tensor, TensorFlags},
+};
+
+/// Returns the pairs of tensor and candle data types describing the same scalars.
+fn dtypes() -> [(DataType, DType); 11] {
+    [
+        (DataType::u8(), DType::U8),
+        (DataType::u32(), DType::U32),
+        (DataType::i16(), DType::I16),
+        (DataType::i32(), DType::I32),
+        (DataType::i64(), DType::I64),
+        (DataType::bf16(), DType::BF16),
+        (DataType::f16(), DType::F16),
+        (DataType::f32(), DType::F32),
+        (DataType::f64(), DType::F64),
+        (DataType::float8_e4m3fn(), DType::F8E4M3),
+        (DataType::float8_e8m0fnu(), DType::F8E8M0),
+    ]
+}
+
+/// Returns the pointer to the first entry of the storage.
+fn storage_ptr(storage: &CpuStorage) -> *mut c_void {
+    let ptr = match storage {
+        CpuStorage::U8(values) => values.as_ptr() as *const c_void,
+        CpuStorage::U32(values) => values.as_ptr() as *const c_void,
+        CpuStorage::I16(values) => values.as_ptr() as *const c_void,
+        CpuStorage::I32(values) => values.as_ptr() as *const c_void,
+        CpuStorage::I64(values) => values.as_ptr() as *const c_void,
+        CpuStorage::BF16(values) => values.as_ptr() as *const c_void,
+        CpuStorage::F16(values) => values.as_ptr() as *const c_void,
+        CpuStorage::F32(values) => values.as_ptr() as *const c_void,
+        CpuStorage::F64(values) => values.as_ptr() as *const c_void,
+        CpuStorage::F8E4M3(values) => values.as_ptr() as *const c_void,
+        CpuStorage::F6E2M3(values)
+        | CpuStorage::F6E3M2(values)
+        | CpuStorage::F4(values)
+        | CpuStorage::F8E8M0(values) => values.as_ptr() as *const c_void,
+    };
+    ptr as *mut c_void
+}
+
+/// Returns the data, data type, shape, strides (in number of elements) and byte offset of a candle
+/// tensor on cpu.
+#[allow(clippy::type_complexity)]
+fn layout(
+    tensor: &candle_core::Tensor,
+) -> Result<(*mut c_void, DataType, Vec<i64>, Vec<i64>, u64), CandleConversionError> {
+    let dtype = dtypes()
+        .into_iter()
+        .find(|(_, candle)| *candle == tensor.dtype())
+        .map(|(dtype, _)| dtype)
+        .ok_or_else(|| {
+            CandleConversionError::UnsupportedDataType(tensor.dtype().as_str().to_string())
+        })?;
+    let (storage, layout) = tensor.storage_and_layout();
+    let data = match &*storage {
+        Storage::Cpu(storage) => storage_ptr(storage),
+        _ => {
+            return Err(CandleConversionError::NotOnCpu(format!(
+                "{:?}",
+                tensor.device()
+            )))
+        }
+    };
+    let shape = layout.dims().iter().map(|&size| size as i64).collect();
+    let strides = layout
+        .stride()
+        .iter()
+        .map(|&stride| stride as i64)
+        .collect();
+    let byte_offset = (layout.start_offset() * tensor.dtype().size_in_bytes()) as u64;
+    Ok((data, dtype, shape, strides, byte_offset))
+}
+
+/// Exports a candle tensor on cpu as a read-only tensor without copying its entries. The tensor
+/// keeps the storage alive.
+impl<C> TryFrom<&candle_core::Tensor> for ManagedTensorVersioned<'static, C> {
+    type Error = CandleConversionError;
+
+    fn try_from(tensor: &candle_core::Tensor) -> Result<Self, Self::Error> {
+        let (data, dtype, shape, strides, byte_offset) = layout(tensor)?;
+        Ok(ManagedTensorVersioned::from_any_owner(
+            tensor.clone(),
+            data,
+            Device::default(),
+            dtype,
+            shape,
+            strides,
+            byte_offset,
+        )
+        .with_flags(TensorFlags::READ_ONLY))
+    }
+}
+
+/// Copies the entries of a host tensor, in any layout, into a new contiguous candle tensor on cpu.
+impl TryFrom<&Tensor<'_>> for candle_core::Tensor {
+    type Error = CandleConversionError;
+
+    fn try_from(tensor: &Tensor<'_>) -> Result<Self, Self::Error> {
+        let dtype = tensor.dtype();
+        let candle = dtypes()
+            .into_iter()
+            .find(|(found, _)| *found == dtype)
+            .map(|(_, candle)| candle)
+            .ok_or_else(|| CandleConversionError::UnsupportedDataType(dtype.to_string()))?;
+        tensor.check_host()?;
+        tensor.validate().map_err(TensorAccessError::from)?;
+        let shape = tensor.shape().map_err(TensorAccessError::from)?;
+        let numel = tensor.numel().map_err(TensorAccessError::from)?;
+        let itemsize = tensor.itemsize();
+        let first = (tensor.data() as *const u8).wrapping_add(tensor.byte_offset() as usize);
+        let bytes = if numel == 0 {
+            Vec::new()
+        } else if tensor.is_c_contiguous() {
+            unsafe { slice::from_raw_parts(first, numel * itemsize) }.to_vec()
+        } else {
+            let shape = tensor.shape_i64().unwrap_or_default();
+            let strides = tensor
+                .effective_strides()
+                .map_err(TensorAccessError::from)?;
+            let mut bytes = Vec::with_capacity(numel * itemsize);
+            all_offsets(shape, &strides, |offset| {
+                let entry = first.wrapping_offset(offset as isize * itemsize as isize);
+                bytes.extend_from_slice(unsafe { slice::from_raw_parts(entry, itemsize) });
+                true
+            });
+            bytes
+        };
+        Ok(candle_core::Tensor::from_raw_buffer(
+            &bytes,
+            candle,
+            &shape,
+            &CandleDevice::Cpu,
+        )?)
+    }
+}
+
+impl<C> ManagedTensor<'static, C> {
+    /// Copies the entries of a host tensor into a new candle tensor on cpu. Candle tensors are only
+    /// exported as read-only ManagedTensorVersioneds, which take them back without copying.
+    pub fn into_candle(self) -> Result<candle_core::Tensor, CandleConversionError> {
+        candle_core::Tensor::try_from(self.tensor())
+    }
+}
+
+impl<C> ManagedTensorVersioned<'static, C> {
+    /// Takes back the candle tensor a ManagedTensorVersioned has been exported from without
+    /// copying, or copies the entries of any other host tensor into a new candle tensor on cpu.
+    pub fn into_candle(self) -> Result<candle_core::Tensor, CandleConversionError> {
+        match self.into_owner::<candle_core::Tensor>() {
+            Ok(tensor) => Ok(tensor),
+            Err(mt) => candle_core::Tensor::try_from(mt.tensor()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use candle_core::IndexOp;
+
+    #[test]
+    fn round_trip() {
+        let t = candle_core::Tensor::arange(0i64, 12, &CandleDevice::Cpu)
+            .unwrap()
+            .reshape((3, 4))
+            .unwrap()
+            .i((1.., 1..3))
+            .unwrap();
+        let mt = ManagedTensorVersioned::<()>::try_from(&t).unwrap();
+        assert!(mt.is_read_only());
+        assert_eq!(mt.tensor().shape().unwrap(), vec![2, 2]);
+        assert_eq!(mt.tensor().strides(), Some(vec![4, 1]));
+        assert_eq!(mt.tensor().byte_offset(), 5 * 8);
+        let copy = candle_core::Tensor::try_from(mt.tensor()).unwrap();
+        assert_eq!(
+            copy.to_vec2::<i64>().unwrap(),
+            vec![vec![5, 6], vec![9, 10]]
+        );
+        let back = mt.into_candle().unwrap();
+        assert_eq!(back.id(), t.id());
+
+        let t = candle_core::Tensor::ones((2, 2), DType::BF16, &CandleDevice::Cpu).unwrap();
+        let mt = ManagedTensorVersioned::<()>::try_from(&t).unwrap();
+        assert_eq!(mt.tensor().dtype(), DataType::bf16());
+
+        let t = candle_core::Tensor::ones(3, DType::F8E4M3, &CandleDevice::Cpu).unwrap();
+        let mt = ManagedTensorVersioned::<()>::try_from(&t).unwrap();
+        assert_eq!(mt.tensor().dtype(), DataType::float8_e4m3fn());
+        let t = candle_core::Tensor::try_from(mt.tensor()).unwrap();
+        assert_eq!(
+            t.to_dtype(DType::F32).unwrap().to_vec1::<f32>().unwrap(),
+            [1.; 3]
+        );
+    }
+
+    #[test]
+    fn copy_from_tensor() {
+        let mt = ManagedTensor::from_vec(vec![1f32, 2., 3., 4., 5., 6.], &[2, 3]).unwrap();
+        let t = mt.into_candle().unwrap();
+        assert_eq!(
+            t.to_vec2::<f32>().unwrap(),
+            vec![vec![1., 2., 3.], vec![4., 5., 6.]]
+        );
+
+        let mut data = [1u8, 2, 3, 4, 5, 6];
+        // the transpose, with a negative stride
+        let view = Tensor::builder()
+            .data(&mut data)
+            .shape([3, 2])
+            .strides([-1, 3])
+            .byte_offset(2)
+            .build()
+            .unwrap();
+        let t = candle_core::Tensor::try_from(view.tensor()).unwrap();
+        assert_eq!(
+            t.to_vec2::<u8>().unwrap(),
+            vec![vec![3, 6], vec![2, 5], vec![1, 4]]
+        );
+
+        let mt = ManagedTensor::from_vec(vec![true], &[1]).unwrap();
+        assert!(matches!(
+            mt.into_candle(),
+            Err(CandleConversionError::UnsupportedDataType(_))
+        ));
+    }
+}
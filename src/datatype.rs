@@ -1,14 +1,25 @@
 #![allow(non_upper_case_globals)]
 
-use std::convert::TryFrom;
+use std::{
+    convert::TryFrom,
+    fmt::{self, Display, Formatter},
+    mem,
+    str::FromStr,
+};
 
 use crate::{
-    errors::UnsupportedDataTypeCode,
+    errors::{ParseDataTypeError, UnsupportedDataTypeCode},
     ffi::{
-        DLDataType, DLDataTypeCode, DLDataTypeCode_kDLBfloat, DLDataTypeCode_kDLComplex,
-        DLDataTypeCode_kDLFloat, DLDataTypeCode_kDLInt, DLDataTypeCode_kDLOpaqueHandle,
-        DLDataTypeCode_kDLUInt,
+        DLDataType, DLDataTypeCode, DLDataTypeCode_kDLBfloat, DLDataTypeCode_kDLBool,
+        DLDataTypeCode_kDLComplex, DLDataTypeCode_kDLFloat, DLDataTypeCode_kDLFloat4_e2m1fn,
+        DLDataTypeCode_kDLFloat6_e2m3fn, DLDataTypeCode_kDLFloat6_e3m2fn,
+        DLDataTypeCode_kDLFloat8_e3m4, DLDataTypeCode_kDLFloat8_e4m3,
+        DLDataTypeCode_kDLFloat8_e4m3b11fnuz, DLDataTypeCode_kDLFloat8_e4m3fn,
+        DLDataTypeCode_kDLFloat8_e4m3fnuz, DLDataTypeCode_kDLFloat8_e5m2,
+        DLDataTypeCode_kDLFloat8_e5m2fnuz, DLDataTypeCode_kDLFloat8_e8m0fnu, DLDataTypeCode_kDLInt,
+        DLDataTypeCode_kDLOpaqueHandle, DLDataTypeCode_kDLUInt,
     },
+    minifloat::Format,
 };
 
 /// See [DLDataTypeCode](https://dmlc.github.io/dlpack/latest/c_api.html#_CPPv414DLDataTypeCode)
@@ -21,6 +32,18 @@ pub enum DataTypeCode {
     OpaqueHandle = 3,
     Bfloat = 4,
     Complex = 5,
+    Bool = 6,
+    Float8E3M4 = 7,
+    Float8E4M3 = 8,
+    Float8E4M3B11Fnuz = 9,
+    Float8E4M3Fn = 10,
+    Float8E4M3Fnuz = 11,
+    Float8E5M2 = 12,
+    Float8E5M2Fnuz = 13,
+    Float8E8M0Fnu = 14,
+    Float6E2M3Fn = 15,
+    Float6E3M2Fn = 16,
+    Float4E2M1Fn = 17,
 }
 
 impl From<DataTypeCode> for u8 {
@@ -32,6 +55,18 @@ impl From<DataTypeCode> for u8 {
             DataTypeCode::OpaqueHandle => 3,
             DataTypeCode::Bfloat => 4,
             DataTypeCode::Complex => 5,
+            DataTypeCode::Bool => 6,
+            DataTypeCode::Float8E3M4 => 7,
+            DataTypeCode::Float8E4M3 => 8,
+            DataTypeCode::Float8E4M3B11Fnuz => 9,
+            DataTypeCode::Float8E4M3Fn => 10,
+            DataTypeCode::Float8E4M3Fnuz => 11,
+            DataTypeCode::Float8E5M2 => 12,
+            DataTypeCode::Float8E5M2Fnuz => 13,
+            DataTypeCode::Float8E8M0Fnu => 14,
+            DataTypeCode::Float6E2M3Fn => 15,
+            DataTypeCode::Float6E3M2Fn => 16,
+            DataTypeCode::Float4E2M1Fn => 17,
         }
     }
 }
@@ -45,6 +80,18 @@ impl<'a> From<&'a DataTypeCode> for DLDataTypeCode {
             DataTypeCode::OpaqueHandle => DLDataTypeCode_kDLOpaqueHandle,
             DataTypeCode::Bfloat => DLDataTypeCode_kDLBfloat,
             DataTypeCode::Complex => DLDataTypeCode_kDLComplex,
+            DataTypeCode::Bool => DLDataTypeCode_kDLBool,
+            DataTypeCode::Float8E3M4 => DLDataTypeCode_kDLFloat8_e3m4,
+            DataTypeCode::Float8E4M3 => DLDataTypeCode_kDLFloat8_e4m3,
+            DataTypeCode::Float8E4M3B11Fnuz => DLDataTypeCode_kDLFloat8_e4m3b11fnuz,
+            DataTypeCode::Float8E4M3Fn => DLDataTypeCode_kDLFloat8_e4m3fn,
+            DataTypeCode::Float8E4M3Fnuz => DLDataTypeCode_kDLFloat8_e4m3fnuz,
+            DataTypeCode::Float8E5M2 => DLDataTypeCode_kDLFloat8_e5m2,
+            DataTypeCode::Float8E5M2Fnuz => DLDataTypeCode_kDLFloat8_e5m2fnuz,
+            DataTypeCode::Float8E8M0Fnu => DLDataTypeCode_kDLFloat8_e8m0fnu,
+            DataTypeCode::Float6E2M3Fn => DLDataTypeCode_kDLFloat6_e2m3fn,
+            DataTypeCode::Float6E3M2Fn => DLDataTypeCode_kDLFloat6_e3m2fn,
+            DataTypeCode::Float4E2M1Fn => DLDataTypeCode_kDLFloat4_e2m1fn,
         }
     }
 }
@@ -59,11 +106,94 @@ impl TryFrom<DLDataTypeCode> for DataTypeCode {
             DLDataTypeCode_kDLOpaqueHandle => Ok(DataTypeCode::OpaqueHandle),
             DLDataTypeCode_kDLBfloat => Ok(DataTypeCode::Bfloat),
             DLDataTypeCode_kDLComplex => Ok(DataTypeCode::Complex),
+            DLDataTypeCode_kDLBool => Ok(DataTypeCode::Bool),
+            DLDataTypeCode_kDLFloat8_e3m4 => Ok(DataTypeCode::Float8E3M4),
+            DLDataTypeCode_kDLFloat8_e4m3 => Ok(DataTypeCode::Float8E4M3),
+            DLDataTypeCode_kDLFloat8_e4m3b11fnuz => Ok(DataTypeCode::Float8E4M3B11Fnuz),
+            DLDataTypeCode_kDLFloat8_e4m3fn => Ok(DataTypeCode::Float8E4M3Fn),
+            DLDataTypeCode_kDLFloat8_e4m3fnuz => Ok(DataTypeCode::Float8E4M3Fnuz),
+            DLDataTypeCode_kDLFloat8_e5m2 => Ok(DataTypeCode::Float8E5M2),
+            DLDataTypeCode_kDLFloat8_e5m2fnuz => Ok(DataTypeCode::Float8E5M2Fnuz),
+            DLDataTypeCode_kDLFloat8_e8m0fnu => Ok(DataTypeCode::Float8E8M0Fnu),
+            DLDataTypeCode_kDLFloat6_e2m3fn => Ok(DataTypeCode::Float6E2M3Fn),
+            DLDataTypeCode_kDLFloat6_e3m2fn => Ok(DataTypeCode::Float6E3M2Fn),
+            DLDataTypeCode_kDLFloat4_e2m1fn => Ok(DataTypeCode::Float4E2M1Fn),
             _ => Err(UnsupportedDataTypeCode(code.to_string())),
         }
     }
 }
 
+impl DataTypeCode {
+    /// Returns the name of the code, which is the prefix of the names of its data types, e.g.
+    /// `"float"` or `"float8_e4m3fn"`.
+    pub const fn name(&self) -> &'static str {
+        match self {
+            DataTypeCode::Int => "int",
+            DataTypeCode::UInt => "uint",
+            DataTypeCode::Float => "float",
+            DataTypeCode::OpaqueHandle => "handle",
+            DataTypeCode::Bfloat => "bfloat",
+            DataTypeCode::Complex => "complex",
+            DataTypeCode::Bool => "bool",
+            DataTypeCode::Float8E3M4 => "float8_e3m4",
+            DataTypeCode::Float8E4M3 => "float8_e4m3",
+            DataTypeCode::Float8E4M3B11Fnuz => "float8_e4m3b11fnuz",
+            DataTypeCode::Float8E4M3Fn => "float8_e4m3fn",
+            DataTypeCode::Float8E4M3Fnuz => "float8_e4m3fnuz",
+            DataTypeCode::Float8E5M2 => "float8_e5m2",
+            DataTypeCode::Float8E5M2Fnuz => "float8_e5m2fnuz",
+            DataTypeCode::Float8E8M0Fnu => "float8_e8m0fnu",
+            DataTypeCode::Float6E2M3Fn => "float6_e2m3fn",
+            DataTypeCode::Float6E3M2Fn => "float6_e3m2fn",
+            DataTypeCode::Float4E2M1Fn => "float4_e2m1fn",
+        }
+    }
+
+    /// Returns the number of bits of the float8, float6 and float4 codes, which are fixed.
+    pub fn minifloat_bits(&self) -> Option<u8> {
+        Format::of(*self).map(|format| format.bits() as u8)
+    }
+
+    /// Returns the value of the float8, float6 or float4 bits, or `None` for the other codes. Only
+    /// the low bits of the format are read, e.g. the 4 low bits for float4.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use dlpackrs::DataTypeCode;
+    /// assert_eq!(DataTypeCode::Float8E4M3Fn.decode_f32(0x7e), Some(448.));
+    /// assert_eq!(DataTypeCode::Float4E2M1Fn.decode_f32(0x7), Some(6.));
+    /// assert_eq!(DataTypeCode::Float.decode_f32(0), None);
+    /// ```
+    pub fn decode_f32(&self, bits: u8) -> Option<f32> {
+        Format::of(*self).map(|format| format.decode(bits))
+    }
+
+    /// Returns the float8, float6 or float4 bits of the value rounded to the nearest representable
+    /// one, ties to even, or `None` for the other codes.
+    ///
+    /// Values beyond the largest finite one become infinities if the format has them, NaN if it has
+    /// one and the largest finite value otherwise. NaN is `None` for formats without NaN.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use dlpackrs::DataTypeCode;
+    /// assert_eq!(DataTypeCode::Float8E5M2.encode_f32(1.), Some(0x3c));
+    /// assert_eq!(DataTypeCode::Float8E4M3Fn.encode_f32(1000.), Some(0x7f));
+    /// assert_eq!(DataTypeCode::Float6E2M3Fn.encode_f32(-1000.), Some(0x3f));
+    /// ```
+    pub fn encode_f32(&self, value: f32) -> Option<u8> {
+        Format::of(*self).and_then(|format| format.encode(value))
+    }
+}
+
+impl Display for DataTypeCode {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
 /// DLPack DataType. See [DLDataType](https://dmlc.github.io/dlpack/latest/c_api.html#_CPPv410DLDataType)
 ///
 /// ## Example
@@ -84,9 +214,9 @@ pub struct DataType {
 impl From<DataType> for DLDataType {
     fn from(dtype: DataType) -> Self {
         Self {
-            code: dtype.code as u8,
-            bits: dtype.bits as u8,
-            lanes: dtype.lanes as u16,
+            code: dtype.code,
+            bits: dtype.bits,
+            lanes: dtype.lanes,
         }
     }
 }
@@ -101,6 +231,23 @@ impl From<DLDataType> for DataType {
     }
 }
 
+// every `DLDataType` is a valid `DataType`
+const _: () = assert!(mem::size_of::<DataType>() == mem::size_of::<DLDataType>());
+const _: () = assert!(mem::align_of::<DataType>() == mem::align_of::<DLDataType>());
+
+/// Reinterprets a `DLDataType` in place.
+impl<'a> From<&'a DLDataType> for &'a DataType {
+    fn from(dtype: &'a DLDataType) -> Self {
+        unsafe { &*(dtype as *const DLDataType as *const DataType) }
+    }
+}
+
+impl AsRef<DLDataType> for DataType {
+    fn as_ref(&self) -> &DLDataType {
+        unsafe { &*(self as *const DataType as *const DLDataType) }
+    }
+}
+
 impl DataType {
     pub const fn new(code: u8, bits: u8, lanes: u16) -> DataType {
         DataType { code, bits, lanes }
@@ -164,6 +311,11 @@ impl DataType {
         DataType::new(DataTypeCode::Float.into(), bits, lanes)
     }
 
+    /// IEEE 754 half precision type.
+    pub fn f16() -> DataType {
+        Self::float(16, 1)
+    }
+
     pub fn f32() -> DataType {
         Self::float(32, 1)
     }
@@ -182,8 +334,356 @@ impl DataType {
         DataType::new(DataTypeCode::Bfloat.into(), bits, lanes)
     }
 
+    /// Brain floating point type with 16 bits.
+    pub fn bf16() -> DataType {
+        Self::bfloat(16, 1)
+    }
+
     /// Mathematical Complex type.
     pub fn complex(bits: u8, lanes: u16) -> DataType {
         DataType::new(DataTypeCode::Complex.into(), bits, lanes)
     }
+
+    /// Boolean type stored in a byte.
+    pub fn bool() -> DataType {
+        DataType::new(DataTypeCode::Bool.into(), 8, 1)
+    }
+
+    /// Float8 type with 3 exponent and 4 mantissa bits, infinities and NaNs.
+    pub fn float8_e3m4() -> DataType {
+        DataType::new(DataTypeCode::Float8E3M4.into(), 8, 1)
+    }
+
+    /// Float8 type with 4 exponent and 3 mantissa bits, infinities and NaNs.
+    pub fn float8_e4m3() -> DataType {
+        DataType::new(DataTypeCode::Float8E4M3.into(), 8, 1)
+    }
+
+    /// Float8 type with 4 exponent and 3 mantissa bits, an exponent bias of 11 and a single NaN.
+    pub fn float8_e4m3b11fnuz() -> DataType {
+        DataType::new(DataTypeCode::Float8E4M3B11Fnuz.into(), 8, 1)
+    }
+
+    /// Float8 type with 4 exponent and 3 mantissa bits and no infinities.
+    pub fn float8_e4m3fn() -> DataType {
+        DataType::new(DataTypeCode::Float8E4M3Fn.into(), 8, 1)
+    }
+
+    /// Float8 type with 4 exponent and 3 mantissa bits and a single NaN.
+    pub fn float8_e4m3fnuz() -> DataType {
+        DataType::new(DataTypeCode::Float8E4M3Fnuz.into(), 8, 1)
+    }
+
+    /// Float8 type with 5 exponent and 2 mantissa bits, infinities and NaNs.
+    pub fn float8_e5m2() -> DataType {
+        DataType::new(DataTypeCode::Float8E5M2.into(), 8, 1)
+    }
+
+    /// Float8 type with 5 exponent and 2 mantissa bits and a single NaN.
+    pub fn float8_e5m2fnuz() -> DataType {
+        DataType::new(DataTypeCode::Float8E5M2Fnuz.into(), 8, 1)
+    }
+
+    /// Unsigned float8 type holding powers of two.
+    pub fn float8_e8m0fnu() -> DataType {
+        DataType::new(DataTypeCode::Float8E8M0Fnu.into(), 8, 1)
+    }
+
+    /// Float6 type with 2 exponent and 3 mantissa bits, packed.
+    pub fn float6_e2m3fn() -> DataType {
+        DataType::new(DataTypeCode::Float6E2M3Fn.into(), 6, 1)
+    }
+
+    /// Float6 type with 3 exponent and 2 mantissa bits, packed.
+    pub fn float6_e3m2fn() -> DataType {
+        DataType::new(DataTypeCode::Float6E3M2Fn.into(), 6, 1)
+    }
+
+    /// Float4 type with 2 exponent bits and 1 mantissa bit, packed.
+    pub fn float4_e2m1fn() -> DataType {
+        DataType::new(DataTypeCode::Float4E2M1Fn.into(), 4, 1)
+    }
+
+    /// Returns the DataType of the Rust scalar type `T`.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use dlpackrs::DataType;
+    /// assert_eq!(DataType::of::<f32>(), DataType::f32());
+    /// assert_eq!(DataType::of::<bool>(), DataType::bool());
+    /// ```
+    pub fn of<T: Element>() -> DataType {
+        T::DTYPE
+    }
+}
+
+impl DataType {
+    /// Returns the number of bits implied by a name without bits, e.g. `"bool"` or `"float8_e4m3fn"`.
+    fn default_bits(code: DataTypeCode) -> Option<u8> {
+        match code {
+            DataTypeCode::Bool => Some(8),
+            DataTypeCode::OpaqueHandle => Some(64),
+            code => code.minifloat_bits(),
+        }
+    }
+}
+
+/// Writes the canonical name of the DataType, made of the name of its code, its number of bits
+/// unless implied by the name and its number of lanes if more than one, e.g. `"float32"`,
+/// `"int8x4"`, `"bool"` or `"float8_e4m3fn"`.
+///
+/// ## Example
+///
+/// ```
+/// use dlpackrs::DataType;
+/// assert_eq!(DataType::bfloat(16, 1).to_string(), "bfloat16");
+/// assert_eq!("uint1".parse::<DataType>().unwrap(), DataType::uint(1, 1));
+/// assert_eq!("complex64x2".parse::<DataType>().unwrap(), DataType::complex(64, 2));
+/// assert!("float".parse::<DataType>().is_err());
+/// ```
+impl Display for DataType {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match DataTypeCode::try_from(self.code as DLDataTypeCode) {
+            Ok(code) if DataType::default_bits(code) == Some(self.bits) => write!(f, "{}", code)?,
+            Ok(code) => write!(f, "{}{}", code, self.bits)?,
+            Err(_) => write!(f, "code{}_{}", self.code, self.bits)?,
+        }
+        if self.lanes != 1 {
+            write!(f, "x{}", self.lanes)?;
+        }
+        Ok(())
+    }
+}
+
+/// Parses the canonical name written by `Display`, failing unless it describes a valid DataType.
+impl FromStr for DataType {
+    type Err = ParseDataTypeError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = || ParseDataTypeError(s.to_string());
+        // the longest name first, so that e.g. "float8_e4m3fnuz" is not read as "float8_e4m3fn"
+        let (code, rest) = (0..=u8::MAX)
+            .filter_map(|code| DataTypeCode::try_from(code as DLDataTypeCode).ok())
+            .filter_map(|code| s.strip_prefix(code.name()).map(|rest| (code, rest)))
+            .min_by_key(|(_, rest)| rest.len())
+            .ok_or_else(error)?;
+        let (bits, lanes) = match rest.split_once('x') {
+            Some((bits, lanes)) => (bits, Some(lanes)),
+            None => (rest, None),
+        };
+        let is_number =
+            |digits: &str| !digits.is_empty() && digits.bytes().all(|b| b.is_ascii_digit());
+        let bits = match bits {
+            "" => DataType::default_bits(code).ok_or_else(error)?,
+            bits if is_number(bits) => bits.parse().map_err(|_| error())?,
+            _ => return Err(error()),
+        };
+        let lanes = match lanes {
+            None => 1,
+            Some(lanes) if is_number(lanes) => lanes.parse().map_err(|_| error())?,
+            Some(_) => return Err(error()),
+        };
+        if bits == 0 || lanes == 0 {
+            return Err(error());
+        }
+        Ok(DataType::new(code.into(), bits, lanes))
+    }
+}
+
+/// Rust scalar types which can be the entries of a Tensor.
+///
+/// # Safety
+///
+/// `DTYPE` must describe the in-memory representation of `Self` exactly and `is_valid` must reject
+/// every byte pattern which is not a valid value of `Self`. `ALL_BITS_VALID` must only be true if
+/// every byte pattern is a valid value of `Self`.
+pub unsafe trait Element: Copy + 'static {
+    const DTYPE: DataType;
+
+    /// Whether every byte pattern is a valid value of `Self`, in which case the entries of a tensor
+    /// need not be checked with `is_valid` before they are accessed.
+    const ALL_BITS_VALID: bool = false;
+
+    /// Returns whether the given bytes hold valid values of `Self`.
+    fn is_valid(_bytes: &[u8]) -> bool {
+        true
+    }
+}
+
+macro_rules! impl_element {
+    ( $( $ty:ty => $code:ident ),+ ) => {
+        $(
+            unsafe impl Element for $ty {
+                const DTYPE: DataType = DataType::new(
+                    DataTypeCode::$code as u8,
+                    (std::mem::size_of::<$ty>() * 8) as u8,
+                    1,
+                );
+                const ALL_BITS_VALID: bool = true;
+            }
+        )+
+    };
+}
+
+impl_element!(
+    i8 => Int,
+    i16 => Int,
+    i32 => Int,
+    i64 => Int,
+    u8 => UInt,
+    u16 => UInt,
+    u32 => UInt,
+    u64 => UInt,
+    f32 => Float,
+    f64 => Float
+);
+
+#[cfg(feature = "half")]
+impl_element!(
+    half::f16 => Float,
+    half::bf16 => Bfloat
+);
+
+#[cfg(feature = "num-complex")]
+impl_element!(
+    num_complex::Complex<f32> => Complex,
+    num_complex::Complex<f64> => Complex
+);
+
+unsafe impl Element for bool {
+    const DTYPE: DataType = DataType::new(DataTypeCode::Bool as u8, 8, 1);
+
+    fn is_valid(bytes: &[u8]) -> bool {
+        bytes.iter().all(|&b| b <= 1)
+    }
+}
+
+/// Rust types unpacking the entries of packed tensors, i.e. of less than a byte, such as int4, uint4,
+/// 1-bit booleans and float4, as well as float8 entries. Entries are packed densely from the least
+/// significant bit of each byte.
+pub trait PackedElement: Copy {
+    /// Returns whether the packed entries of the data type unpack to `Self`.
+    fn accepts(dtype: DataType) -> bool;
+
+    /// Unpacks the low `dtype.bits` bits of `bits`.
+    fn from_bits(bits: u8, dtype: DataType) -> Self;
+
+    /// Packs the value into `dtype.bits` bits, returning `None` if it does not fit.
+    fn to_bits(self, dtype: DataType) -> Option<u8>;
+}
+
+/// Unsigned integers, e.g. uint4, zero-extended.
+impl PackedElement for u8 {
+    fn accepts(dtype: DataType) -> bool {
+        dtype.code == DataTypeCode::UInt as u8
+    }
+
+    fn from_bits(bits: u8, _dtype: DataType) -> Self {
+        bits
+    }
+
+    fn to_bits(self, dtype: DataType) -> Option<u8> {
+        (dtype.bits >= 8 || self >> dtype.bits == 0).then_some(self)
+    }
+}
+
+/// Signed integers, e.g. int4, sign-extended.
+impl PackedElement for i8 {
+    fn accepts(dtype: DataType) -> bool {
+        dtype.code == DataTypeCode::Int as u8
+    }
+
+    fn from_bits(bits: u8, dtype: DataType) -> Self {
+        ((bits << (8 - dtype.bits)) as i8) >> (8 - dtype.bits)
+    }
+
+    fn to_bits(self, dtype: DataType) -> Option<u8> {
+        let width = dtype.bits;
+        let bound = 1_i16 << (width - 1);
+        (-bound..bound)
+            .contains(&(self as i16))
+            .then(|| self as u8 & (u8::MAX >> (8 - width)))
+    }
+}
+
+/// 1-bit booleans.
+impl PackedElement for bool {
+    fn accepts(dtype: DataType) -> bool {
+        dtype.code == DataTypeCode::Bool as u8 && dtype.bits == 1
+    }
+
+    fn from_bits(bits: u8, _dtype: DataType) -> Self {
+        bits != 0
+    }
+
+    fn to_bits(self, _dtype: DataType) -> Option<u8> {
+        Some(self as u8)
+    }
+}
+
+/// Float8, float6 and float4 values, decoded exactly and encoded with [`DataTypeCode::encode_f32`].
+impl PackedElement for f32 {
+    fn accepts(dtype: DataType) -> bool {
+        DataTypeCode::try_from(dtype.code as DLDataTypeCode)
+            .ok()
+            .and_then(|code| code.minifloat_bits())
+            == Some(dtype.bits)
+    }
+
+    fn from_bits(bits: u8, dtype: DataType) -> Self {
+        DataTypeCode::try_from(dtype.code as DLDataTypeCode)
+            .ok()
+            .and_then(|code| code.decode_f32(bits))
+            .unwrap_or(f32::NAN)
+    }
+
+    fn to_bits(self, dtype: DataType) -> Option<u8> {
+        DataTypeCode::try_from(dtype.code as DLDataTypeCode)
+            .ok()
+            .and_then(|code| code.encode_f32(self))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn data_type_names() {
+        let names = [
+            (DataType::f32(), "float32"),
+            (DataType::int(8, 4), "int8x4"),
+            (DataType::bf16(), "bfloat16"),
+            (DataType::uint(1, 1), "uint1"),
+            (DataType::complex(64, 1), "complex64"),
+            (DataType::bool(), "bool"),
+            (DataType::new(DataTypeCode::Bool.into(), 1, 1), "bool1"),
+            (DataType::opaque_handle(64, 1), "handle"),
+            (DataType::float8_e4m3fn(), "float8_e4m3fn"),
+            (DataType::float8_e4m3fnuz(), "float8_e4m3fnuz"),
+            (
+                DataType::new(DataTypeCode::Float4E2M1Fn.into(), 4, 2),
+                "float4_e2m1fnx2",
+            ),
+        ];
+        for (dtype, name) in names {
+            assert_eq!(dtype.to_string(), name);
+            assert_eq!(name.parse::<DataType>().unwrap(), dtype);
+        }
+
+        for code in 0..=17 {
+            for (bits, lanes) in [(1, 1), (4, 1), (8, 1), (16, 4), (255, 65535)] {
+                let dtype = DataType::new(code, bits, lanes);
+                assert_eq!(dtype.to_string().parse::<DataType>().unwrap(), dtype);
+            }
+        }
+
+        for name in [
+            "", "float", "int0", "uint8x0", "float32x", "int-8", "int8x4x2", "float256", "f32",
+        ] {
+            assert!(name.parse::<DataType>().is_err(), "{}", name);
+        }
+        assert_eq!(DataType::new(42, 8, 1).to_string(), "code42_8");
+    }
 }
@@ -1,6 +1,6 @@
-use enumn::N;
-
+use std::convert::TryFrom;
 use std::fmt::{self, Display, Formatter};
+use std::mem;
 use std::str::FromStr;
 
 use crate::errors::UnsupportedDeviceError;
@@ -8,30 +8,87 @@ use crate::ffi;
 
 /// DLPack device type. See [DLDeviceType](https://dmlc.github.io/dlpack/latest/c_api.html#_CPPv412DLDeviceType)
 ///
+/// The known device types are associated constants. Any other value, e.g. a device type added by a
+/// newer version of DLPack, is kept as is so that tensors on such devices can still be inspected and
+/// passed through. It is displayed and parsed as its number.
+///
 /// ## Example
 ///
 /// ```
 /// use dlpackrs::DeviceType;
-/// let cpu = DeviceType::from("cpu");
+/// let cpu = "cpu".parse::<DeviceType>().unwrap();
 /// println!("device is: {}", cpu);
+/// assert!("tpu".parse::<DeviceType>().is_err());
+/// let unknown = DeviceType::from(42);
+/// assert!(!unknown.is_known());
+/// assert_eq!(unknown.to_string().parse::<DeviceType>().unwrap(), unknown);
 ///```
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, N)]
-#[repr(u32)]
-pub enum DeviceType {
-    CPU = 1,
-    CUDA = 2,
-    CUDAHost = 3,
-    OpenCL = 4,
-    Vulkan = 7,
-    Metal = 8,
-    VPI = 9,
-    ROCM = 10,
-    ROCMHost = 11,
-    ExtDev = 12,
-    CUDAManaged = 13,
-    OneAPI = 14,
-    WebGPU = 15,
-    Hexagon = 16,
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(transparent)]
+pub struct DeviceType(pub u32);
+
+macro_rules! device_types {
+    (
+        $( $( #[$meta:meta] )* $variant:ident = $code:literal => $name:literal $( | $alias:literal )* ),+
+    ) => {
+        #[allow(non_upper_case_globals)]
+        impl DeviceType {
+            $(
+                $( #[$meta] )*
+                pub const $variant: DeviceType = DeviceType($code);
+            )+
+
+            /// Returns the name written by `Display`, unless the device type is unknown.
+            pub const fn name(&self) -> Option<&'static str> {
+                match self.0 {
+                    $( $code => Some($name), )+
+                    _ => None,
+                }
+            }
+
+            fn variant(&self) -> Option<&'static str> {
+                match self.0 {
+                    $( $code => Some(stringify!($variant)), )+
+                    _ => None,
+                }
+            }
+
+            fn from_name(name: &str) -> Option<Self> {
+                match name {
+                    $( $name $( | $alias )* => Some(DeviceType::$variant), )+
+                    _ => None,
+                }
+            }
+        }
+    };
+}
+
+device_types!(
+    CPU = 1 => "cpu",
+    CUDA = 2 => "cuda" | "nvptx",
+    CUDAHost = 3 => "cuda_host",
+    OpenCL = 4 => "opencl" | "cl",
+    Vulkan = 7 => "vulkan",
+    Metal = 8 => "metal",
+    VPI = 9 => "vpi",
+    ROCM = 10 => "rocm",
+    ROCMHost = 11 => "rocm_host",
+    ExtDev = 12 => "ext_dev" | "ext_device",
+    CUDAManaged = 13 => "cuda_managed",
+    OneAPI = 14 => "one_api",
+    WebGPU = 15 => "web_gpu",
+    Hexagon = 16 => "hexagon",
+    MAIA = 17 => "maia",
+    /// AWS Trainium, `kDLTrn = 18` as assigned by DLPack 1.2. The DLPack 1.1 header the bindings
+    /// are generated from stops at `kDLMAIA = 17`, so this value is not checked against it.
+    Trn = 18 => "trn"
+);
+
+impl DeviceType {
+    /// Returns whether the device type is one of the associated constants.
+    pub const fn is_known(&self) -> bool {
+        self.name().is_some()
+    }
 }
 
 impl Default for DeviceType {
@@ -43,81 +100,94 @@ impl Default for DeviceType {
 
 impl From<DeviceType> for ffi::DLDeviceType {
     fn from(device_type: DeviceType) -> Self {
-        device_type as Self
+        device_type.0
     }
 }
 
 impl From<ffi::DLDeviceType> for DeviceType {
     fn from(device_type: ffi::DLDeviceType) -> Self {
-        Self::n(device_type as _).expect("invalid enumeration value for ffi::DLDeviceType")
+        DeviceType(device_type)
+    }
+}
+
+impl fmt::Debug for DeviceType {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self.variant() {
+            Some(variant) => f.write_str(variant),
+            None => f.debug_tuple("DeviceType").field(&self.0).finish(),
+        }
     }
 }
 
 impl Display for DeviceType {
     fn fmt(&self, f: &mut Formatter) -> fmt::Result {
-        write!(
-            f,
-            "{}",
-            match self {
-                DeviceType::CPU => "cpu",
-                DeviceType::CUDA => "cuda",
-                DeviceType::CUDAHost => "cuda_host",
-                DeviceType::OpenCL => "opencl",
-                DeviceType::Vulkan => "vulkan",
-                DeviceType::Metal => "metal",
-                DeviceType::VPI => "vpi",
-                DeviceType::ROCM => "rocm",
-                DeviceType::ROCMHost => "rocm_host",
-                DeviceType::ExtDev => "ext_device",
-                DeviceType::CUDAManaged => "cuda_managed",
-                DeviceType::OneAPI => "one_api",
-                DeviceType::WebGPU => "web_gpu",
-                DeviceType::Hexagon => "hexagon",
-            }
-        )
-    }
-}
-
-impl<'a> From<&'a str> for DeviceType {
-    fn from(type_str: &'a str) -> Self {
-        match type_str {
-            "cpu" => DeviceType::CPU,
-            "cuda" => DeviceType::CUDA,
-            "cuda_host" => DeviceType::CUDAHost,
-            "opencl" => DeviceType::OpenCL,
-            "vulkan" => DeviceType::Vulkan,
-            "metal" => DeviceType::Metal,
-            "vpi" => DeviceType::VPI,
-            "rocm" => DeviceType::ROCM,
-            "rocm_host" => DeviceType::ROCMHost,
-            "ext_dev" => DeviceType::ExtDev,
-            "cuda_managed" => DeviceType::CUDAManaged,
-            "one_api" => DeviceType::OneAPI,
-            "web_gpu" => DeviceType::WebGPU,
-            "hexagon" => DeviceType::Hexagon,
-            _ => panic!("{:?} not supported!", type_str),
+        match self.name() {
+            Some(name) => f.write_str(name),
+            None => write!(f, "{}", self.0),
+        }
+    }
+}
+
+/// Parses the names written by `Display` as well as the `"cl"`, `"nvptx"` and `"ext_device"` aliases,
+/// or the number of any device type.
+impl FromStr for DeviceType {
+    type Err = UnsupportedDeviceError;
+    fn from_str(type_str: &str) -> Result<Self, Self::Err> {
+        if let Some(device_type) = DeviceType::from_name(type_str) {
+            return Ok(device_type);
+        }
+        match type_str.parse() {
+            Ok(code) if type_str.bytes().all(|b| b.is_ascii_digit()) => Ok(DeviceType(code)),
+            _ => Err(UnsupportedDeviceError(type_str.to_string())),
         }
     }
 }
 
+impl<'a> TryFrom<&'a str> for DeviceType {
+    type Error = UnsupportedDeviceError;
+    fn try_from(type_str: &'a str) -> Result<Self, Self::Error> {
+        type_str.parse()
+    }
+}
+
 /// DLPack Device. See [DLDevice](https://dmlc.github.io/dlpack/latest/c_api.html#_CPPv48DLDevice)
 ///
 /// ## Example
 ///
 /// ```
 /// use dlpackrs::Device;
-/// let dev = Device::cpu(0);
-/// println!("device: {}", dev);
+/// let dev = Device::cuda(1);
+/// assert_eq!(dev.to_string(), "cuda:1");
+/// assert_eq!("cuda:1".parse::<Device>().unwrap(), dev);
+/// assert_eq!("cpu".parse::<Device>().unwrap(), Device::cpu(0));
+/// ```
+///
+/// A Device has the layout of a `DLDevice`, which it can be reinterpreted from in place:
+///
+/// ```
+/// use std::convert::TryFrom;
+/// use dlpackrs::{ffi::DLDevice, Device};
+/// let dev = DLDevice { device_type: 2, device_id: 1 };
+/// assert_eq!(<&Device>::try_from(&dev).unwrap(), &Device::cuda(1));
+/// let dev = DLDevice { device_type: 2, device_id: -1 };
+/// assert!(<&Device>::try_from(&dev).is_err());
 /// ```
 #[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
 #[repr(C)]
 pub struct Device {
     pub device_type: DeviceType,
-    pub device_id: usize,
+    /// The id of the device, which must be non-negative. It is not checked on construction, but
+    /// the conversions from `DLDevice` and [`Tensor::validate`](crate::Tensor::validate) fail on
+    /// negative ids.
+    pub device_id: i32,
 }
 
+// any `DLDevice` with a non-negative id is a valid `Device`
+const _: () = assert!(mem::size_of::<Device>() == mem::size_of::<ffi::DLDevice>());
+const _: () = assert!(mem::align_of::<Device>() == mem::align_of::<ffi::DLDevice>());
+
 impl Device {
-    pub fn new(device_type: DeviceType, device_id: usize) -> Device {
+    pub fn new(device_type: DeviceType, device_id: i32) -> Device {
         Device {
             device_type,
             device_id,
@@ -129,32 +199,57 @@ impl<'a> From<&'a Device> for ffi::DLDevice {
     fn from(dev: &'a Device) -> Self {
         Self {
             device_type: dev.device_type.into(),
-            device_id: dev.device_id as i32,
+            device_id: dev.device_id,
         }
     }
 }
 
+impl AsRef<ffi::DLDevice> for Device {
+    fn as_ref(&self) -> &ffi::DLDevice {
+        unsafe { &*(self as *const Device as *const ffi::DLDevice) }
+    }
+}
+
 impl Default for Device {
     fn default() -> Self {
         Self {
-            device_type: ffi::DLDeviceType_kDLCPU.into(),
+            device_type: DeviceType::CPU,
             device_id: 0,
         }
     }
 }
 
-impl<'a> From<&'a str> for Device {
-    fn from(target: &str) -> Self {
-        Device::new(DeviceType::from(target), 0)
+impl<'a> TryFrom<&'a str> for Device {
+    type Error = UnsupportedDeviceError;
+    fn try_from(target: &'a str) -> Result<Self, Self::Error> {
+        target.parse()
     }
 }
 
-impl From<ffi::DLDevice> for Device {
-    fn from(dev: ffi::DLDevice) -> Self {
-        Device {
-            device_type: DeviceType::from(dev.device_type),
-            device_id: dev.device_id as usize,
+/// Fails for negative device ids. Unknown device types are kept as is.
+impl TryFrom<ffi::DLDevice> for Device {
+    type Error = UnsupportedDeviceError;
+    fn try_from(dev: ffi::DLDevice) -> Result<Self, Self::Error> {
+        let device_type = DeviceType::from(dev.device_type);
+        if dev.device_id < 0 {
+            return Err(UnsupportedDeviceError(format!(
+                "{}:{}",
+                device_type, dev.device_id
+            )));
         }
+        Ok(Device {
+            device_type,
+            device_id: dev.device_id,
+        })
+    }
+}
+
+/// Reinterprets a `DLDevice` in place, failing for negative device ids.
+impl<'a> TryFrom<&'a ffi::DLDevice> for &'a Device {
+    type Error = UnsupportedDeviceError;
+    fn try_from(dev: &'a ffi::DLDevice) -> Result<Self, Self::Error> {
+        Device::try_from(*dev)?;
+        Ok(unsafe { &*(dev as *const ffi::DLDevice as *const Device) })
     }
 }
 
@@ -162,40 +257,43 @@ impl From<Device> for ffi::DLDevice {
     fn from(dev: Device) -> Self {
         ffi::DLDevice {
             device_type: dev.device_type.into(),
-            device_id: dev.device_id as i32,
+            device_id: dev.device_id,
         }
     }
 }
 
 impl Display for Device {
     fn fmt(&self, f: &mut Formatter) -> fmt::Result {
-        write!(f, "{}({})", self.device_type, self.device_id)
+        write!(f, "{}:{}", self.device_type, self.device_id)
     }
 }
 
-macro_rules! add_device {
-    ( $( $dev_type:ident : [ $( $dev_name:ident ),+ ] ),+ ) => {
-        /// Creates a Device from a string (e.g., "cpu", "cuda")
-        use DeviceType::*;
-        impl FromStr for Device {
-            type Err = UnsupportedDeviceError;
-            fn from_str(type_str: &str) -> Result<Self, Self::Err> {
-                Ok(Self {
-                    device_type: match type_str {
-                         $( $(  stringify!($dev_name)  )|+ => $dev_type.into()),+,
-                        _ => return Err(UnsupportedDeviceError(type_str.to_string())),
-                    },
-                    device_id: 0,
-                })
+/// Parses `"type:id"`, e.g. `"cuda:1"`, or `"type"` for the device of id 0, where the type is
+/// parsed by [`DeviceType::from_str`].
+impl FromStr for Device {
+    type Err = UnsupportedDeviceError;
+    fn from_str(device_str: &str) -> Result<Self, Self::Err> {
+        let error = || UnsupportedDeviceError(device_str.to_string());
+        let (type_str, device_id) = match device_str.split_once(':') {
+            Some((type_str, id_str)) if id_str.bytes().all(|b| b.is_ascii_digit()) => {
+                (type_str, id_str.parse().map_err(|_| error())?)
             }
-        }
+            Some(_) => return Err(error()),
+            None => (device_str, 0),
+        };
+        let device_type = type_str.parse().map_err(|_| error())?;
+        Ok(Device::new(device_type, device_id))
+    }
+}
 
+macro_rules! add_device {
+    ( $( $dev_type:ident : [ $( $dev_name:ident ),+ ] ),+ ) => {
         impl Device {
             $(
                 $(
-                    pub fn $dev_name(device_id: usize) -> Self {
+                    pub fn $dev_name(device_id: i32) -> Self {
                         Self {
-                            device_type: $dev_type.into(),
+                            device_type: DeviceType::$dev_type,
                             device_id,
                         }
                     }
@@ -219,7 +317,9 @@ add_device!(
     CUDAManaged: [cuda_managed],
     OneAPI: [one_api],
     WebGPU: [web_gpu],
-    Hexagon: [hexagon]
+    Hexagon: [hexagon],
+    MAIA: [maia],
+    Trn: [trn]
 );
 
 #[cfg(test)]
@@ -237,5 +337,43 @@ mod tests {
         let str_dev = Device::new(DeviceType::CUDA, 0);
         assert_eq!(str_dev.clone(), str_dev);
         assert_ne!(str_dev, Device::new(DeviceType::CPU, 0));
+
+        let dl_dev: &ffi::DLDevice = str_dev.as_ref();
+        let dev = <&Device>::try_from(dl_dev).unwrap();
+        assert!(std::ptr::eq(dev, &str_dev));
+    }
+
+    #[test]
+    fn device_parsing() {
+        for device_type in (0..=20).map(DeviceType) {
+            let dev = Device::new(device_type, 3);
+            assert_eq!(dev.to_string().parse::<Device>().unwrap(), dev);
+            assert_eq!(
+                device_type.to_string().parse::<DeviceType>().unwrap(),
+                device_type
+            );
+        }
+        assert_eq!(
+            Device::try_from("rocm_host:0").unwrap(),
+            Device::rocm_host(0)
+        );
+        assert_eq!(Device::try_from("cl").unwrap(), Device::cl(0));
+        for device_str in ["tpu", "cuda:", "cuda:-1", "cuda:1:2", ":1", "cuda:x"] {
+            assert!(device_str.parse::<Device>().is_err(), "{}", device_str);
+        }
+        assert_eq!(Device::try_from("maia:1").unwrap(), Device::maia(1));
+        assert_eq!(format!("{:?}", DeviceType::MAIA), "MAIA");
+        assert_eq!(format!("{:?}", DeviceType(42)), "DeviceType(42)");
+        let dev = ffi::DLDevice {
+            device_type: 42,
+            device_id: 1,
+        };
+        assert_eq!(Device::try_from(dev).unwrap().to_string(), "42:1");
+        let dev = ffi::DLDevice {
+            device_type: 1,
+            device_id: -1,
+        };
+        assert!(Device::try_from(dev).is_err());
+        assert!(Device::try_from(ffi::DLDevice::from(Device::cuda(-1))).is_err());
     }
 }
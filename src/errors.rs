@@ -1,5 +1,7 @@
 use thiserror::Error;
 
+use crate::{DataType, Device};
+
 #[derive(Debug, Error)]
 #[error("unsupported device: {0}")]
 pub struct UnsupportedDeviceError(pub String);
@@ -7,3 +9,124 @@ pub struct UnsupportedDeviceError(pub String);
 #[derive(Debug, Error)]
 #[error("unsupported data type code: {0}")]
 pub struct UnsupportedDataTypeCode(pub String);
+
+#[derive(Debug, Error)]
+#[error("invalid data type: {0}")]
+pub struct ParseDataTypeError(pub String);
+
+#[derive(Debug, Error)]
+#[error("unsupported DLPack version: {0}.{1}")]
+pub struct UnsupportedVersionError(pub u32, pub u32);
+
+#[derive(Debug, Error)]
+#[error("shape {shape:?} does not match a buffer of {len} elements")]
+pub struct ShapeMismatchError {
+    pub shape: Vec<usize>,
+    pub len: usize,
+}
+
+#[derive(Debug, Error)]
+pub enum TensorAccessError {
+    #[error("data type mismatch: expected {expected}, found {found}")]
+    DataTypeMismatch { expected: DataType, found: DataType },
+    #[error("tensor data is not accessible from the host: {0}")]
+    NotOnHost(Device),
+    #[error("tensor is not compact row-major")]
+    NotContiguous,
+    #[error("tensor data is null")]
+    NullData,
+    #[error("tensor data is not aligned to {0} bytes")]
+    Misaligned(usize),
+    #[error("tensor holds values which are not valid for its data type")]
+    InvalidValue,
+    #[error("tensor is read-only")]
+    ReadOnly,
+    #[error("tensor entries overlap in memory")]
+    Overlapping,
+    #[error("tensor shape {0:?} does not fit the requested type")]
+    IncompatibleShape(Vec<i64>),
+    #[error("tensor strides {0:?} do not fit the requested type")]
+    IncompatibleStrides(Vec<i64>),
+    #[error("data type {0} does not fit the requested type")]
+    IncompatibleDataType(DataType),
+    #[error("index {0:?} is out of bounds")]
+    IndexOutOfBounds(Vec<usize>),
+    #[error("value does not fit in {0} bits")]
+    ValueOutOfRange(u8),
+    #[error(transparent)]
+    Invalid(ValidationError),
+}
+
+impl From<ValidationError> for TensorAccessError {
+    fn from(e: ValidationError) -> Self {
+        match e {
+            ValidationError::NullData(_) => TensorAccessError::NullData,
+            ValidationError::Misaligned(align) => TensorAccessError::Misaligned(align),
+            e => TensorAccessError::Invalid(e),
+        }
+    }
+}
+
+#[cfg(feature = "arrow")]
+#[derive(Debug, Error)]
+pub enum ArrowConversionError {
+    #[error("arrow data type {0} has no tensor counterpart")]
+    UnsupportedDataType(String),
+    #[error("arrow array holds {0} nulls")]
+    Nulls(usize),
+    #[error("shape {shape:?} does not match lists of {len} values")]
+    ShapeMismatch { shape: Vec<usize>, len: usize },
+    #[error("lists of shape {0:?} hold more values than an arrow list can")]
+    ValueLengthOverflow(Vec<usize>),
+    #[error("tensor is not owned by a vector or an arrow buffer")]
+    UnsupportedOwner,
+    #[error(transparent)]
+    Access(#[from] TensorAccessError),
+    #[error(transparent)]
+    Arrow(#[from] arrow_schema::ArrowError),
+}
+
+#[cfg(feature = "candle")]
+#[derive(Debug, Error)]
+pub enum CandleConversionError {
+    #[error("data type {0} has no counterpart")]
+    UnsupportedDataType(String),
+    #[error("candle tensor on {0} is not on cpu")]
+    NotOnCpu(String),
+    #[error(transparent)]
+    Access(#[from] TensorAccessError),
+    #[error(transparent)]
+    Candle(#[from] candle_core::Error),
+}
+
+#[derive(Debug, Error)]
+pub enum ValidationError {
+    #[error(transparent)]
+    UnsupportedDevice(#[from] UnsupportedDeviceError),
+    #[error(transparent)]
+    UnsupportedDataTypeCode(#[from] UnsupportedDataTypeCode),
+    #[error("invalid data type: {0:?}")]
+    InvalidDataType(DataType),
+    #[error("negative number of dimensions: {0}")]
+    NegativeNdim(i32),
+    #[error("null shape with {0} dimensions")]
+    NullShape(i32),
+    #[error("negative size {size} of dimension {dim}")]
+    NegativeShape { dim: usize, size: i64 },
+    #[error("number of elements overflows")]
+    ElementCountOverflow,
+    #[error("size in bytes overflows")]
+    ByteSizeOverflow,
+    #[error("null data with {0} elements")]
+    NullData(usize),
+    #[error("strides reach before the start of the data")]
+    StridesOutOfBounds,
+    #[error("data is not aligned to {0} bytes")]
+    Misaligned(usize),
+    #[error("{strides} strides for {ndim} dimensions")]
+    StridesMismatch { ndim: usize, strides: usize },
+    #[error("entries reach past the end of the {0} bytes of data")]
+    DataOutOfBounds(usize),
+    #[error("data of type {from} can not be reinterpreted as {to}")]
+    InvalidReinterpretation { from: DataType, to: DataType },
+}
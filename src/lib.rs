@@ -15,7 +15,7 @@
 //! and [CuPy](https://docs.cupy.dev/en/stable/reference/generated/cupy.fromDlpack.html).
 //! An important feature of this standard is to provide *zero-cost* tensor conversion across frameworks on a particular supported hardware.
 //!
-//! The Minimum Supported Rust Version (MSRV) is the stable toolchain **1.57.0**.
+//! The Minimum Supported Rust Version (MSRV) is the stable toolchain **1.85.0**.
 //!
 //! ## Usage
 //!
@@ -24,73 +24,63 @@
 //! ### Memory Managed Tensor
 //!
 //! In this case, `ManagedTensor` is built from `ManagedTensorProxy` which is a safe proxy for the unsafe `ffi::DLManagedTensor`.
+//! Tensors exchanged following DLPack 1.x are represented by `ManagedTensorVersioned` which wraps the unsafe `ffi::DLManagedTensorVersioned`.
 //!
 //! ### Plain Not-Memory-Managed Tensor
 //!
 //! In this case, the (invariant) Rust wrapper `Tensor` can be used or if needed the unsafe `ffi::DLTensor`.
+//! `TensorView` and `TensorViewMut` borrow a slice and store the shape and strides their `DLTensor` points to, so that it stays valid as long as the view lives. `Tensor::builder` builds views of any layout, checking that they fit the borrowed slice.
 //!
 //! <br>
 //!
 //! ## Example
 //!
+//! With the `ndarray` feature, ndarray views convert to and from tensors at zero-cost, whatever their
+//! strides are.
+//!
 //! When ownership is concerned, one can use the `ManagedTensor`. Here is an example on how the bi-directional conversion
 //!
-//! <div align="center">ndarray::ArrayD <---> ManagedTensor</div>
+//! <div align="center">ndarray::ArrayViewMutD <---> ManagedTensor</div>
 //!
 //! is done at zero-cost.
 //!
-//! ```no_run
-//! impl<'tensor, C> From<&'tensor mut ArrayD<f32>> for ManagedContext<'tensor, C> {
-//!     fn from(t: &'tensor mut ArrayD<f32>) -> Self {
-//!         let dlt: Tensor<'tensor> = Tensor::from(t);
-//!         let inner = DLManagedTensor::new(dlt.0, None);
-//!         ManagedContext(inner)
-//!     }
-//! }
-//!
-//! impl<'tensor, C> From<&mut ManagedContext<'tensor, C>> for ArrayD<f32> {
-//!     fn from(mt: &mut ManagedContext<'tensor, C>) -> Self {
-//!         let dlt: DLTensor = mt.0.inner.dl_tensor.into();
-//!         unsafe {
-//!             let arr = RawArrayViewMut::from_shape_ptr(dlt.shape().unwrap(), dlt.data() as *mut f32);
-//!             arr.deref_into_view_mut().into_dyn().to_owned()
-//!         }
-//!     }
-//! }
+//! ```
+//! # #[cfg(feature = "ndarray")]
+//! # {
+//! use std::convert::TryFrom;
+//! use dlpackrs::ManagedTensor;
+//! use ndarray::{array, ArrayViewMutD};
+//!
+//! let mut arr = array![[1f32, 2., 3.], [4., 5., 6.]];
+//! let mut mt = ManagedTensor::from(arr.view_mut());
+//! let mut view = ArrayViewMutD::<f32>::try_from(mt.tensor_mut()).unwrap();
+//! view[[1, 2]] = 0.;
+//! drop(mt);
+//! assert_eq!(arr[[1, 2]], 0.);
+//! # }
 //! ```
 //!
 //! <br>
 //!
-//! And when ownership is not concerned, one can use `Tensor` as a view. Here is an example on how the bi-directional converion
+//! And when ownership is not concerned, one can use `Tensor` as a view. Here is an example on how the conversion
 //!
-//! <div align="center">ndarray::ArrayD <---> Tensor</div>
+//! <div align="center">Tensor ---> ndarray::ArrayViewD</div>
 //!
-//! is done at zero-cost.
+//! is done at zero-cost, where a read-only `ManagedTensorVersioned` holds the shape and strides of the array view.
 //!
-//! ```no_run
-//! impl<'tensor> From<&'tensor mut ArrayD<f32>> for Tensor<'tensor> {
-//!     fn from(arr: &'tensor mut ArrayD<f32>) -> Self {
-//!         let inner = DLTensor::new(
-//!             arr.as_mut_ptr() as *mut c_void,
-//!             Device::default(),
-//!             arr.ndim() as i32,
-//!             DataType::f32(),
-//!             arr.shape().as_ptr() as *const _ as *mut i64,
-//!             arr.strides().as_ptr() as *const _ as *mut i64,
-//!             0,
-//!         );
-//!         Tensor(inner)
-//!     }
-//! }
-//!
-//! impl<'tensor> From<&'tensor mut Tensor<'tensor>> for ArrayD<f32> {
-//!     fn from(t: &'tensor mut Tensor<'tensor>) -> Self {
-//!         unsafe {
-//!             let arr = RawArrayViewMut::from_shape_ptr(t.0.shape().unwrap(), t.0.data() as *mut f32);
-//!             arr.deref_into_view_mut().into_dyn().to_owned()
-//!         }
-//!     }
-//! }
+//! ```
+//! # #[cfg(feature = "ndarray")]
+//! # {
+//! use std::convert::TryFrom;
+//! use dlpackrs::ManagedTensorVersioned;
+//! use ndarray::{array, ArrayViewD};
+//!
+//! let arr = array![[1f32, 2., 3.], [4., 5., 6.]];
+//! let mt = ManagedTensorVersioned::from(arr.t());
+//! let t = mt.tensor();
+//! assert!(t.is_f_contiguous());
+//! assert_eq!(ArrayViewD::<f32>::try_from(t).unwrap(), arr.t().into_dyn());
+//! # }
 //! ```
 //!
 //! See the complete [examples/sample](https://github.com/ehsanmok/dlpackrs/blob/main/examples/sample/src/main.rs)
@@ -106,15 +96,47 @@ pub mod datatype;
 pub mod device;
 pub mod errors;
 pub mod tensor;
+pub mod view;
+
+mod minifloat;
 
-pub use datatype::{DataType, DataTypeCode};
+#[cfg(feature = "arrow")]
+mod arrow;
+#[cfg(feature = "candle")]
+mod candle;
+#[cfg(feature = "nalgebra")]
+mod nalgebra;
+#[cfg(feature = "ndarray")]
+mod ndarray;
+#[cfg(feature = "pyo3")]
+pub mod python;
+
+pub use datatype::{DataType, DataTypeCode, Element, PackedElement};
 pub use device::{Device, DeviceType};
-pub use tensor::{ManagedTensor, ManagedTensorProxy, ManagerContext, Tensor};
+pub use tensor::{
+    ManagedTensor, ManagedTensorProxy, ManagedTensorVersioned, ManagerContext, Tensor, TensorFlags,
+    TensorMut,
+};
+pub use view::{TensorView, TensorViewMut};
+
+// the bindings must come from the DLPack 1.x header, which the submodule is expected to hold
+const _: () = assert!(ffi::DLPACK_MAJOR_VERSION >= 1);
 
+/// Returns the DLPack version the crate is built against, encoded as before DLPack 1.0, e.g. 80 for
+/// DLPack 0.8 and 110 for DLPack 1.1.
 pub fn version() -> u32 {
-    ffi::DLPACK_VERSION
+    ffi::DLPACK_MAJOR_VERSION * 100 + ffi::DLPACK_MINOR_VERSION * 10
+}
+
+/// Returns the DLPack version the crate is built against, as stored in versioned tensors.
+pub fn dlpack_version() -> ffi::DLPackVersion {
+    ffi::DLPackVersion {
+        major: ffi::DLPACK_MAJOR_VERSION,
+        minor: ffi::DLPACK_MINOR_VERSION,
+    }
 }
 
+/// Returns the DLPack ABI version, which is the major version since DLPack 1.0.
 pub fn abi_version() -> u32 {
-    ffi::DLPACK_ABI_VERSION
+    ffi::DLPACK_MAJOR_VERSION
 }
@@ -0,0 +1,257 @@
+//! Bit-exact conversions between `f32` and the float8, float6 and float4 formats of DLPack 1.1,
+//! following the naming of [ml_dtypes](https://github.com/jax-ml/ml_dtypes).
+
+use crate::datatype::DataTypeCode;
+
+/// Encodings of the special values of a format.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Special {
+    /// Infinities and NaNs as in IEEE 754, with the largest exponent.
+    Ieee,
+    /// No infinities and NaNs whose exponent and mantissa bits are all ones.
+    Fn,
+    /// No infinities, no negative zero and a single NaN encoded as negative zero.
+    Fnuz,
+    /// Neither infinities nor NaNs.
+    Finite,
+    /// Unsigned powers of two without zero, whose NaN bits are all ones.
+    E8M0,
+}
+
+/// Layout of a floating point format of at most 8 bits.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Format {
+    exponent: u32,
+    mantissa: u32,
+    bias: i32,
+    special: Special,
+}
+
+impl Format {
+    /// Returns the format of the code, if it is one of the float8, float6 and float4 codes.
+    pub(crate) fn of(code: DataTypeCode) -> Option<Format> {
+        let (exponent, mantissa, bias, special) = match code {
+            DataTypeCode::Float8E3M4 => (3, 4, 3, Special::Ieee),
+            DataTypeCode::Float8E4M3 => (4, 3, 7, Special::Ieee),
+            DataTypeCode::Float8E4M3B11Fnuz => (4, 3, 11, Special::Fnuz),
+            DataTypeCode::Float8E4M3Fn => (4, 3, 7, Special::Fn),
+            DataTypeCode::Float8E4M3Fnuz => (4, 3, 8, Special::Fnuz),
+            DataTypeCode::Float8E5M2 => (5, 2, 15, Special::Ieee),
+            DataTypeCode::Float8E5M2Fnuz => (5, 2, 16, Special::Fnuz),
+            DataTypeCode::Float8E8M0Fnu => (8, 0, 127, Special::E8M0),
+            DataTypeCode::Float6E2M3Fn => (2, 3, 1, Special::Finite),
+            DataTypeCode::Float6E3M2Fn => (3, 2, 3, Special::Finite),
+            DataTypeCode::Float4E2M1Fn => (2, 1, 1, Special::Finite),
+            _ => return None,
+        };
+        Some(Format {
+            exponent,
+            mantissa,
+            bias,
+            special,
+        })
+    }
+
+    /// Returns the number of bits of a value.
+    pub(crate) fn bits(&self) -> u32 {
+        self.exponent + self.mantissa + (self.special != Special::E8M0) as u32
+    }
+
+    fn sign_bit(&self) -> u8 {
+        match self.special {
+            Special::E8M0 => 0,
+            _ => 1 << (self.exponent + self.mantissa),
+        }
+    }
+
+    fn exponent_mask(&self) -> u32 {
+        (1 << self.exponent) - 1
+    }
+
+    fn mantissa_mask(&self) -> u32 {
+        (1 << self.mantissa) - 1
+    }
+
+    /// Returns the bits of the NaN, if the format has one.
+    fn nan(&self) -> Option<u8> {
+        let ones = (self.exponent_mask() << self.mantissa) as u8;
+        match self.special {
+            Special::Ieee => Some(ones | 1 << (self.mantissa - 1)),
+            Special::Fn | Special::E8M0 => Some(ones | self.mantissa_mask() as u8),
+            Special::Fnuz => Some(self.sign_bit()),
+            Special::Finite => None,
+        }
+    }
+
+    /// Returns the unsigned bits of the largest finite value.
+    fn max_finite(&self) -> u32 {
+        let ones = (self.exponent_mask() << self.mantissa) | self.mantissa_mask();
+        match self.special {
+            Special::Ieee => ones - (1 << self.mantissa),
+            Special::Fn | Special::E8M0 => ones - 1,
+            Special::Fnuz | Special::Finite => ones,
+        }
+    }
+
+    /// Returns the value of the given bits, of which only the low `bits()` ones are read.
+    pub(crate) fn decode(&self, bits: u8) -> f32 {
+        let bits = bits & ((1_u16 << self.bits()) - 1) as u8;
+        let magnitude = (bits & !self.sign_bit()) as u32;
+        let is_nan = match self.special {
+            Special::Fn => Some(magnitude as u8) == self.nan(),
+            Special::Fnuz | Special::E8M0 => Some(bits) == self.nan(),
+            Special::Ieee | Special::Finite => false,
+        };
+        if is_nan {
+            return f32::NAN;
+        }
+        let exponent = (magnitude >> self.mantissa) as i32;
+        let mantissa = (magnitude & self.mantissa_mask()) as f64;
+        if self.special == Special::Ieee && exponent == self.exponent_mask() as i32 {
+            return if mantissa == 0. {
+                if bits & self.sign_bit() != 0 {
+                    f32::NEG_INFINITY
+                } else {
+                    f32::INFINITY
+                }
+            } else {
+                f32::NAN
+            };
+        }
+        let value = if exponent == 0 && self.special != Special::E8M0 {
+            mantissa * 2_f64.powi(1 - self.bias - self.mantissa as i32)
+        } else {
+            (1. + mantissa / (1 << self.mantissa) as f64) * 2_f64.powi(exponent - self.bias)
+        };
+        // every value is exactly representable as f32
+        let value = value as f32;
+        if bits & self.sign_bit() != 0 {
+            -value
+        } else {
+            value
+        }
+    }
+
+    /// Returns the bits of the value rounded to the nearest representable one, ties to even.
+    ///
+    /// Values beyond the largest finite one become infinities if the format has them, NaN if it has
+    /// one and the largest finite value otherwise. Returns `None` for NaN if the format has no NaN.
+    pub(crate) fn encode(&self, value: f32) -> Option<u8> {
+        if value.is_nan() {
+            return self.nan();
+        }
+        let negative = value.is_sign_negative();
+        if self.special == Special::E8M0 && (negative || value == 0.) {
+            return self.nan();
+        }
+        let sign = if negative { self.sign_bit() } else { 0 };
+        let magnitude = value.abs() as f64;
+        let unsigned = if magnitude.is_infinite() {
+            self.max_finite() + 1
+        } else if magnitude == 0. {
+            0
+        } else {
+            self.round(magnitude)
+        };
+        if unsigned > self.max_finite() {
+            return match self.special {
+                Special::Ieee => Some(sign | (self.exponent_mask() << self.mantissa) as u8),
+                Special::Fn | Special::Fnuz | Special::E8M0 => self.nan(),
+                Special::Finite => Some(sign | self.max_finite() as u8),
+            };
+        }
+        // formats without negative zero round negative values to an unsigned zero
+        if unsigned == 0 && self.special == Special::Fnuz {
+            return Some(0);
+        }
+        Some(sign | unsigned as u8)
+    }
+
+    /// Returns the unsigned bits of the finite positive value rounded to the nearest representable
+    /// one, possibly beyond the largest finite one.
+    fn round(&self, magnitude: f64) -> u32 {
+        let min_exponent = match self.special {
+            Special::E8M0 => -self.bias,
+            _ => 1 - self.bias,
+        };
+        // the exponent of f64 values converted from f32 is exact
+        let exponent = ((magnitude.to_bits() >> 52) & 0x7ff) as i32 - 1023;
+        let quantum = exponent.max(min_exponent) - self.mantissa as i32;
+        let scaled = magnitude * 2_f64.powi(-quantum);
+        let floor = scaled.floor();
+        let fraction = scaled - floor;
+        let mut n = floor as u32;
+        if fraction > 0.5 || (fraction == 0.5 && n % 2 == 1) {
+            n += 1;
+        }
+        if self.special == Special::E8M0 {
+            // powers of two: n is 0, 1 or 2
+            let code = quantum + self.bias + (n == 2) as i32;
+            return code.max(0) as u32;
+        }
+        // carries into the exponent are handled by the addition of the biased exponent
+        if n < 1 << self.mantissa {
+            n
+        } else {
+            (((quantum + self.mantissa as i32 + self.bias) as u32) << self.mantissa) + n
+                - (1 << self.mantissa)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ffi::DLDataTypeCode;
+    use std::convert::TryFrom;
+
+    #[test]
+    fn round_trip() {
+        for code in 7..=17 {
+            let code = DataTypeCode::try_from(code as DLDataTypeCode).unwrap();
+            let format = Format::of(code).unwrap();
+            for bits in 0..1_u16 << format.bits() {
+                let (bits, value) = (bits as u8, format.decode(bits as u8));
+                if value.is_nan() {
+                    assert_eq!(format.encode(value), format.nan(), "{:?}", code);
+                } else if !(value == 0. && format.special == Special::Fnuz) {
+                    assert_eq!(format.encode(value), Some(bits), "{:?} {}", code, value);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn special_values() {
+        let e4m3fn = Format::of(DataTypeCode::Float8E4M3Fn).unwrap();
+        assert_eq!(e4m3fn.decode(0x7e), 448.);
+        assert!(e4m3fn.decode(0xff).is_nan());
+        assert_eq!(e4m3fn.encode(1.), Some(0x38));
+        assert_eq!(e4m3fn.encode(464.), Some(0x7e));
+        assert_eq!(e4m3fn.encode(480.), Some(0x7f));
+        assert_eq!(e4m3fn.decode(0x01), 2_f32.powi(-9));
+
+        let e5m2 = Format::of(DataTypeCode::Float8E5M2).unwrap();
+        assert_eq!(e5m2.decode(0x7c), f32::INFINITY);
+        assert_eq!(e5m2.encode(1e6), Some(0x7c));
+        assert_eq!(e5m2.encode(-57344.), Some(0xfb));
+
+        let e4m3fnuz = Format::of(DataTypeCode::Float8E4M3Fnuz).unwrap();
+        assert!(e4m3fnuz.decode(0x80).is_nan());
+        assert_eq!(e4m3fnuz.encode(-0.), Some(0));
+        assert_eq!(e4m3fnuz.decode(0x7f), 240.);
+
+        let e8m0 = Format::of(DataTypeCode::Float8E8M0Fnu).unwrap();
+        assert_eq!(e8m0.decode(127), 1.);
+        assert_eq!(e8m0.decode(0), 2_f32.powi(-127));
+        assert_eq!(e8m0.encode(3.), Some(129));
+        assert_eq!(e8m0.encode(-1.), Some(0xff));
+
+        let e2m1 = Format::of(DataTypeCode::Float4E2M1Fn).unwrap();
+        let values = (0..8).map(|bits| e2m1.decode(bits)).collect::<Vec<_>>();
+        assert_eq!(values, [0., 0.5, 1., 1.5, 2., 3., 4., 6.]);
+        assert_eq!(e2m1.encode(-100.), Some(0xf));
+        assert_eq!(e2m1.encode(2.5), Some(0x4));
+        assert_eq!(e2m1.encode(f32::NAN), None);
+    }
+}
@@ -0,0 +1,319 @@
+//! Zero-copy conversions between [nalgebra](https://docs.rs/nalgebra) matrices and tensors.
+//!
+//! A matrix is a tensor of shape `[nrows, ncols]` whose strides are the row and column strides of
+//! the matrix, which makes a `DMatrix` column-major. One dimensional tensors convert to column
+//! vectors.
+//!
+//! ## Example
+//!
+//! ```
+//! use std::convert::TryFrom;
+//! use dlpackrs::ManagedTensorVersioned;
+//! use nalgebra::{DMatrix, DMatrixView, Dyn};
+//!
+//! let m = DMatrix::from_row_slice(2, 3, &[1f64, 2., 3., 4., 5., 6.]);
+//! let mt = ManagedTensorVersioned::from(m.columns(1, 2));
+//! assert_eq!(mt.tensor().strides(), Some(vec![1, 2]));
+//! let view = DMatrixView::<f64, Dyn, Dyn>::try_from(mt.tensor()).unwrap();
+//! assert_eq!(view, m.columns(1, 2));
+//! ```
+
+use std::{convert::TryFrom, os::raw::c_void};
+
+use nalgebra::{
+    DMatrix, Dim, Matrix, MatrixView, MatrixViewMut, Scalar, ViewStorage, ViewStorageMut,
+};
+
+use crate::{
+    datatype::Element,
+    device::Device,
+    errors::TensorAccessError,
+    tensor::{ManagedTensor, ManagedTensorVersioned, Tensor, TensorFlags, TensorMut},
+};
+
+/// Returns the dimension `D` of the given size, if it fits.
+fn dim<D: Dim>(size: i64) -> Option<D> {
+    let size = usize::try_from(size).ok()?;
+    match D::try_to_usize() {
+        Some(fixed) if fixed != size => None,
+        _ => Some(D::from_usize(size)),
+    }
+}
+
+/// Returns the matrix shape and strides of a tensor of one or two dimensions, checking that they
+/// fit the dimensions `R`, `C` and the strides `RS`, `CS`. Strides of size-1 dimensions are ignored.
+#[allow(clippy::type_complexity)]
+fn matrix_layout<R: Dim, C: Dim, RS: Dim, CS: Dim>(
+    shape: &[i64],
+    strides: &[i64],
+) -> Result<((R, C), (RS, CS)), TensorAccessError> {
+    let (shape, strides) = match (shape, strides) {
+        (&[nrows], &[stride]) => match stride.checked_mul(nrows) {
+            Some(cstride) => ([nrows, 1], [stride, cstride]),
+            None => return Err(TensorAccessError::IncompatibleStrides(strides.to_vec())),
+        },
+        (&[nrows, ncols], &[rstride, cstride]) => ([nrows, ncols], [rstride, cstride]),
+        _ => return Err(TensorAccessError::IncompatibleShape(shape.to_vec())),
+    };
+    let incompatible_shape = || TensorAccessError::IncompatibleShape(shape.to_vec());
+    let incompatible_strides = || TensorAccessError::IncompatibleStrides(strides.to_vec());
+    let stride = |size: i64, stride: i64| match size {
+        0 | 1 => 0,
+        _ => stride,
+    };
+    let rstride = match RS::try_to_usize() {
+        Some(fixed) if shape[0] <= 1 => RS::from_usize(fixed),
+        _ => dim(stride(shape[0], strides[0])).ok_or_else(incompatible_strides)?,
+    };
+    let cstride = match CS::try_to_usize() {
+        Some(fixed) if shape[1] <= 1 => CS::from_usize(fixed),
+        _ => dim(stride(shape[1], strides[1])).ok_or_else(incompatible_strides)?,
+    };
+    Ok((
+        (
+            dim(shape[0]).ok_or_else(incompatible_shape)?,
+            dim(shape[1]).ok_or_else(incompatible_shape)?,
+        ),
+        (rstride, cstride),
+    ))
+}
+
+/// Returns the shape and strides (in number of elements) of a matrix as i64.
+fn layout(
+    (nrows, ncols): (usize, usize),
+    (rstride, cstride): (usize, usize),
+) -> (Vec<i64>, Vec<i64>) {
+    (
+        vec![nrows as i64, ncols as i64],
+        vec![rstride as i64, cstride as i64],
+    )
+}
+
+/// Views a host tensor of `T` with one or two dimensions and non-negative strides as a matrix.
+impl<'a, T, R, C, RS, CS> TryFrom<&'a Tensor<'_>> for MatrixView<'a, T, R, C, RS, CS>
+where
+    T: Element + Scalar,
+    R: Dim,
+    C: Dim,
+    RS: Dim,
+    CS: Dim,
+{
+    type Error = TensorAccessError;
+
+    fn try_from(tensor: &'a Tensor<'_>) -> Result<Self, Self::Error> {
+        let (ptr, shape, strides) = tensor.checked_entries::<T>()?;
+        let (shape, strides) = matrix_layout(shape, &strides)?;
+        let storage = unsafe { ViewStorage::from_raw_parts(ptr as *const T, shape, strides) };
+        Ok(Matrix::from_data(storage))
+    }
+}
+
+/// Views a host tensor of `T` with one or two dimensions and non-negative strides as a mutable
+/// matrix, as long as distinct indices address distinct entries.
+impl<'a, T, R, C, RS, CS> TryFrom<&'a mut Tensor<'_>> for MatrixViewMut<'a, T, R, C, RS, CS>
+where
+    T: Element + Scalar,
+    R: Dim,
+    C: Dim,
+    RS: Dim,
+    CS: Dim,
+{
+    type Error = TensorAccessError;
+
+    fn try_from(tensor: &'a mut Tensor<'_>) -> Result<Self, Self::Error> {
+        if !tensor.is_non_overlapping() {
+            return Err(TensorAccessError::Overlapping);
+        }
+        let (ptr, shape, strides) = tensor.checked_entries::<T>()?;
+        let (shape, strides) = matrix_layout(shape, &strides)?;
+        let storage = unsafe { ViewStorageMut::from_raw_parts(ptr, shape, strides) };
+        Ok(Matrix::from_data(storage))
+    }
+}
+
+/// Same as the conversion of `&mut Tensor`, e.g. for [`ManagedTensor::tensor_mut`].
+impl<'a, T, R, C, RS, CS> TryFrom<TensorMut<'a, '_>> for MatrixViewMut<'a, T, R, C, RS, CS>
+where
+    T: Element + Scalar,
+    R: Dim,
+    C: Dim,
+    RS: Dim,
+    CS: Dim,
+{
+    type Error = TensorAccessError;
+
+    fn try_from(tensor: TensorMut<'a, '_>) -> Result<Self, Self::Error> {
+        Self::try_from(tensor.0)
+    }
+}
+
+/// Exports a matrix view as a read-only tensor on cpu which owns its shape and strides.
+impl<'a, T, R, C, RS, CS> From<MatrixView<'a, T, R, C, RS, CS>> for ManagedTensorVersioned<'a, T>
+where
+    T: Element + Scalar,
+    R: Dim,
+    C: Dim,
+    RS: Dim,
+    CS: Dim,
+{
+    fn from(view: MatrixView<'a, T, R, C, RS, CS>) -> Self {
+        let (shape, strides) = layout(view.shape(), view.strides());
+        let data = view.as_ptr() as *mut c_void;
+        ManagedTensorVersioned::from_owner(
+            view,
+            data,
+            Device::default(),
+            T::DTYPE,
+            shape,
+            strides,
+            0,
+        )
+        .with_flags(TensorFlags::READ_ONLY)
+    }
+}
+
+/// Exports a mutable matrix view as a tensor on cpu which owns its shape and strides.
+impl<'a, T, R, C, RS, CS> From<MatrixViewMut<'a, T, R, C, RS, CS>> for ManagedTensorVersioned<'a, T>
+where
+    T: Element + Scalar,
+    R: Dim,
+    C: Dim,
+    RS: Dim,
+    CS: Dim,
+{
+    fn from(mut view: MatrixViewMut<'a, T, R, C, RS, CS>) -> Self {
+        let (shape, strides) = layout(view.shape(), view.strides());
+        let data = view.as_mut_ptr() as *mut c_void;
+        ManagedTensorVersioned::from_owner(
+            view,
+            data,
+            Device::default(),
+            T::DTYPE,
+            shape,
+            strides,
+            0,
+        )
+    }
+}
+
+/// Exports a mutable matrix view as a tensor on cpu which owns its shape and strides.
+impl<'a, T, R, C, RS, CS> From<MatrixViewMut<'a, T, R, C, RS, CS>> for ManagedTensor<'a, T>
+where
+    T: Element + Scalar,
+    R: Dim,
+    C: Dim,
+    RS: Dim,
+    CS: Dim,
+{
+    fn from(mut view: MatrixViewMut<'a, T, R, C, RS, CS>) -> Self {
+        let (shape, strides) = layout(view.shape(), view.strides());
+        let data = view.as_mut_ptr() as *mut c_void;
+        ManagedTensor::from_owner(view, data, Device::default(), T::DTYPE, shape, strides, 0)
+    }
+}
+
+impl<'tensor, T: Element + Scalar> ManagedTensor<'tensor, T> {
+    /// Moves a matrix into a column-major ManagedTensor on cpu without copying its data, which is
+    /// dropped along with the matrix once the deleter is called.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use dlpackrs::ManagedTensor;
+    /// use nalgebra::DMatrix;
+    ///
+    /// let mt = ManagedTensor::from_dmatrix(DMatrix::from_element(2, 3, 1u8));
+    /// assert!(mt.tensor().is_f_contiguous());
+    /// assert_eq!(mt.into_dmatrix().unwrap(), DMatrix::from_element(2, 3, 1u8));
+    /// ```
+    pub fn from_dmatrix(mut matrix: DMatrix<T>) -> Self {
+        let (shape, strides) = layout(matrix.shape(), matrix.strides());
+        let data = matrix.as_mut_ptr() as *mut c_void;
+        ManagedTensor::from_any_owner(matrix, data, Device::default(), T::DTYPE, shape, strides, 0)
+    }
+
+    /// Takes back the matrix of a ManagedTensor created by [`ManagedTensor::from_dmatrix`] without
+    /// copying its data, or returns the ManagedTensor as is if it has been created otherwise.
+    pub fn into_dmatrix(self) -> Result<DMatrix<T>, Self> {
+        self.into_owner()
+    }
+}
+
+impl<'tensor, T: Element + Scalar> ManagedTensorVersioned<'tensor, T> {
+    /// Same as [`ManagedTensor::from_dmatrix`] for the versioned layout.
+    pub fn from_dmatrix(mut matrix: DMatrix<T>) -> Self {
+        let (shape, strides) = layout(matrix.shape(), matrix.strides());
+        let data = matrix.as_mut_ptr() as *mut c_void;
+        ManagedTensorVersioned::from_any_owner(
+            matrix,
+            data,
+            Device::default(),
+            T::DTYPE,
+            shape,
+            strides,
+            0,
+        )
+    }
+
+    /// Same as [`ManagedTensor::into_dmatrix`] for the versioned layout.
+    pub fn into_dmatrix(self) -> Result<DMatrix<T>, Self> {
+        self.into_owner()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nalgebra::{DMatrixView, DMatrixViewMut, DVectorView, Dyn, Matrix2x3, U1, U3};
+
+    #[test]
+    fn matrix_round_trip() {
+        let mut m = DMatrix::from_fn(3, 4, |i, j| (i * 4 + j) as f32);
+        let expected = m.view_with_steps((0, 1), (2, 2), (1, 1)).clone_owned();
+        let mut mt = ManagedTensor::from(m.view_with_steps_mut((0, 1), (2, 2), (1, 1)));
+        let t = mt.tensor_mut();
+        assert_eq!(t.shape().unwrap(), vec![2, 2]);
+        assert_eq!(t.strides(), Some(vec![2, 6]));
+        assert_eq!(
+            DMatrixView::<f32, Dyn, Dyn>::try_from(&*t).unwrap(),
+            expected
+        );
+        assert!(matches!(
+            DMatrixView::<f32>::try_from(&*t),
+            Err(TensorAccessError::IncompatibleStrides(_))
+        ));
+        assert!(matches!(
+            MatrixView::<f32, U3, Dyn, Dyn, Dyn>::try_from(&*t),
+            Err(TensorAccessError::IncompatibleShape(_))
+        ));
+        DMatrixViewMut::<f32, Dyn, Dyn>::try_from(t).unwrap()[(1, 1)] = -1.;
+        drop(mt);
+        assert_eq!(m[(2, 3)], -1.);
+
+        let m = Matrix2x3::new(1i16, 2, 3, 4, 5, 6);
+        let mt = ManagedTensorVersioned::from(m.row(1));
+        assert!(mt.is_read_only());
+        assert_eq!(mt.tensor().strides(), Some(vec![1, 2]));
+        let row = MatrixView::<i16, U1, Dyn, U1, Dyn>::try_from(mt.tensor()).unwrap();
+        assert_eq!(row, m.row(1));
+    }
+
+    #[test]
+    fn vector_from_tensor() {
+        let mt = ManagedTensor::from_vec(vec![1u32, 2, 3, 4, 5, 6], &[6]).unwrap();
+        let v = DVectorView::<u32>::try_from(mt.tensor()).unwrap();
+        assert_eq!(v.as_slice(), &[1, 2, 3, 4, 5, 6]);
+        let mt = ManagedTensor::from_vec(vec![1u32; 8], &[2, 2, 2]).unwrap();
+        assert!(matches!(
+            DVectorView::<u32>::try_from(mt.tensor()),
+            Err(TensorAccessError::IncompatibleShape(_))
+        ));
+        assert!(ManagedTensor::from_dmatrix(DMatrix::<u32>::zeros(0, 2))
+            .into_dmatrix()
+            .is_ok());
+        assert!(matches!(
+            matrix_layout::<Dyn, U1, Dyn, Dyn>(&[2], &[1 << 62]),
+            Err(TensorAccessError::IncompatibleStrides(_))
+        ));
+    }
+}
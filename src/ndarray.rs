@@ -0,0 +1,325 @@
+//! Zero-copy conversions between [ndarray](https://docs.rs/ndarray) views and tensors.
+//!
+//! ## Example
+//!
+//! ```
+//! use std::convert::TryFrom;
+//! use dlpackrs::ManagedTensorVersioned;
+//! use ndarray::{s, array, ArrayViewD};
+//!
+//! let arr = array![[1f32, 2., 3.], [4., 5., 6.]];
+//! // the rows in reverse order, i.e. with a negative stride
+//! let mt = ManagedTensorVersioned::from(arr.slice(s![..;-1, ..]));
+//! assert!(mt.is_read_only());
+//! assert_eq!(mt.tensor().strides(), Some(vec![-3, 1]));
+//! let view = ArrayViewD::<f32>::try_from(mt.tensor()).unwrap();
+//! assert_eq!(view, arr.slice(s![..;-1, ..]).into_dyn());
+//! ```
+
+use std::{convert::TryFrom, mem, os::raw::c_void};
+
+use ndarray::{
+    Array, ArrayD, ArrayView, ArrayViewD, ArrayViewMut, ArrayViewMutD, Axis, Dimension, IxDyn,
+    ShapeBuilder,
+};
+
+use crate::{
+    datatype::Element,
+    device::Device,
+    errors::TensorAccessError,
+    tensor::{ManagedTensor, ManagedTensorVersioned, Tensor, TensorFlags, TensorMut},
+};
+
+/// Returns the shape and the absolute strides of the entries along with the offset (in number of
+/// elements) of the lowest addressed entry and the axes with negative strides.
+fn positive_layout(shape: &[i64], strides: &[i64]) -> (IxDyn, IxDyn, isize, Vec<Axis>) {
+    let mut low = 0;
+    let mut inverted = Vec::new();
+    if !shape.contains(&0) {
+        for (axis, (&size, &stride)) in shape.iter().zip(strides).enumerate() {
+            if stride < 0 && size > 1 {
+                low += (size - 1) * stride;
+                inverted.push(Axis(axis));
+            }
+        }
+    }
+    let shape = shape.iter().map(|&size| size as usize).collect::<Vec<_>>();
+    let strides = shape
+        .iter()
+        .zip(strides)
+        .map(|(&size, &stride)| {
+            if size > 1 {
+                stride.unsigned_abs() as usize
+            } else {
+                0
+            }
+        })
+        .collect::<Vec<_>>();
+    (IxDyn(&shape), IxDyn(&strides), low as isize, inverted)
+}
+
+/// Returns the pointer to the lowest addressed entry of an ndarray view, which is used as the
+/// data of the tensor, along with the byte offset of the first entry, and the shape and strides (in
+/// number of elements) as i64.
+fn layout<T>(
+    first: *const T,
+    shape: &[usize],
+    strides: &[isize],
+) -> (*mut c_void, u64, Vec<i64>, Vec<i64>) {
+    let mut low = 0;
+    if !shape.contains(&0) {
+        for (&size, &stride) in shape.iter().zip(strides) {
+            if stride < 0 {
+                low += (size as isize - 1) * stride;
+            }
+        }
+    }
+    (
+        first.wrapping_offset(low) as *mut c_void,
+        (-low) as u64 * mem::size_of::<T>() as u64,
+        shape.iter().map(|&size| size as i64).collect(),
+        strides.iter().map(|&stride| stride as i64).collect(),
+    )
+}
+
+/// Views the entries of a host tensor of `T` in any layout, including negative strides.
+impl<'a, T: Element> TryFrom<&'a Tensor<'_>> for ArrayViewD<'a, T> {
+    type Error = TensorAccessError;
+
+    fn try_from(tensor: &'a Tensor<'_>) -> Result<Self, Self::Error> {
+        let (ptr, shape, strides) = tensor.checked_entries::<T>()?;
+        let (shape, strides, low, inverted) = positive_layout(shape, &strides);
+        // ndarray only takes non-negative strides, so the view starts from the lowest addressed
+        // entry and the axes with negative strides are inverted afterwards
+        let mut view =
+            unsafe { ArrayViewD::from_shape_ptr(shape.strides(strides), ptr.wrapping_offset(low)) };
+        for axis in inverted {
+            view.invert_axis(axis);
+        }
+        Ok(view)
+    }
+}
+
+/// Views the entries of a host tensor of `T` mutably in any layout, including negative strides,
+/// as long as distinct indices address distinct entries.
+impl<'a, T: Element> TryFrom<&'a mut Tensor<'_>> for ArrayViewMutD<'a, T> {
+    type Error = TensorAccessError;
+
+    fn try_from(tensor: &'a mut Tensor<'_>) -> Result<Self, Self::Error> {
+        if !tensor.is_non_overlapping() {
+            return Err(TensorAccessError::Overlapping);
+        }
+        let (ptr, shape, strides) = tensor.checked_entries::<T>()?;
+        let (shape, strides, low, inverted) = positive_layout(shape, &strides);
+        let mut view = unsafe {
+            ArrayViewMutD::from_shape_ptr(shape.strides(strides), ptr.wrapping_offset(low))
+        };
+        for axis in inverted {
+            view.invert_axis(axis);
+        }
+        Ok(view)
+    }
+}
+
+/// Same as the conversion of `&mut Tensor`, e.g. for [`ManagedTensor::tensor_mut`].
+impl<'a, T: Element> TryFrom<TensorMut<'a, '_>> for ArrayViewMutD<'a, T> {
+    type Error = TensorAccessError;
+
+    fn try_from(tensor: TensorMut<'a, '_>) -> Result<Self, Self::Error> {
+        Self::try_from(tensor.0)
+    }
+}
+
+/// Exports an ndarray view as a read-only tensor on cpu which owns its shape and strides.
+impl<'a, T: Element, D: Dimension> From<ArrayView<'a, T, D>> for ManagedTensorVersioned<'a, T> {
+    fn from(view: ArrayView<'a, T, D>) -> Self {
+        let (data, byte_offset, shape, strides) =
+            layout(view.as_ptr(), view.shape(), view.strides());
+        ManagedTensorVersioned::from_owner(
+            view,
+            data,
+            Device::default(),
+            T::DTYPE,
+            shape,
+            strides,
+            byte_offset,
+        )
+        .with_flags(TensorFlags::READ_ONLY)
+    }
+}
+
+/// Exports a mutable ndarray view as a tensor on cpu which owns its shape and strides.
+impl<'a, T: Element, D: Dimension> From<ArrayViewMut<'a, T, D>> for ManagedTensorVersioned<'a, T> {
+    fn from(mut view: ArrayViewMut<'a, T, D>) -> Self {
+        let (data, byte_offset, shape, strides) =
+            layout(view.as_mut_ptr(), view.shape(), view.strides());
+        ManagedTensorVersioned::from_owner(
+            view,
+            data,
+            Device::default(),
+            T::DTYPE,
+            shape,
+            strides,
+            byte_offset,
+        )
+    }
+}
+
+/// Exports a mutable ndarray view as a tensor on cpu which owns its shape and strides.
+impl<'a, T: Element, D: Dimension> From<ArrayViewMut<'a, T, D>> for ManagedTensor<'a, T> {
+    fn from(mut view: ArrayViewMut<'a, T, D>) -> Self {
+        let (data, byte_offset, shape, strides) =
+            layout(view.as_mut_ptr(), view.shape(), view.strides());
+        ManagedTensor::from_owner(
+            view,
+            data,
+            Device::default(),
+            T::DTYPE,
+            shape,
+            strides,
+            byte_offset,
+        )
+    }
+}
+
+impl<'tensor, T: Element> ManagedTensor<'tensor, T> {
+    /// Moves an array into a ManagedTensor on cpu without copying its data, which is dropped along
+    /// with the array once the deleter is called.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use dlpackrs::ManagedTensor;
+    /// use ndarray::array;
+    ///
+    /// let arr = array![[1i64, 2], [3, 4]];
+    /// let mt = ManagedTensor::from_ndarray(arr.reversed_axes());
+    /// assert!(mt.tensor().is_f_contiguous());
+    /// let arr = mt.into_ndarray().unwrap();
+    /// assert_eq!(arr, array![[1i64, 3], [2, 4]].into_dyn());
+    /// ```
+    pub fn from_ndarray<D: Dimension>(arr: Array<T, D>) -> Self {
+        let mut arr = arr.into_dyn();
+        let (data, byte_offset, shape, strides) =
+            layout(arr.as_mut_ptr(), arr.shape(), arr.strides());
+        ManagedTensor::from_any_owner(
+            arr,
+            data,
+            Device::default(),
+            T::DTYPE,
+            shape,
+            strides,
+            byte_offset,
+        )
+    }
+
+    /// Takes back the array of a ManagedTensor created by [`ManagedTensor::from_ndarray`] without
+    /// copying its data, or returns the ManagedTensor as is if it has been created otherwise.
+    pub fn into_ndarray(self) -> Result<ArrayD<T>, Self> {
+        self.into_owner()
+    }
+}
+
+impl<'tensor, T: Element> ManagedTensorVersioned<'tensor, T> {
+    /// Same as [`ManagedTensor::from_ndarray`] for the versioned layout.
+    pub fn from_ndarray<D: Dimension>(arr: Array<T, D>) -> Self {
+        let mut arr = arr.into_dyn();
+        let (data, byte_offset, shape, strides) =
+            layout(arr.as_mut_ptr(), arr.shape(), arr.strides());
+        ManagedTensorVersioned::from_any_owner(
+            arr,
+            data,
+            Device::default(),
+            T::DTYPE,
+            shape,
+            strides,
+            byte_offset,
+        )
+    }
+
+    /// Same as [`ManagedTensor::into_ndarray`] for the versioned layout.
+    pub fn into_ndarray(self) -> Result<ArrayD<T>, Self> {
+        self.into_owner()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ndarray::{array, s, Array, IxDyn};
+
+    #[test]
+    fn round_trip() {
+        let mut arr = Array::from_shape_vec((2, 3, 4), (0..24).collect::<Vec<i32>>()).unwrap();
+        let expected = arr.slice(s![.., ..;-2, 1..;2]).to_owned().into_dyn();
+        let mut mt = ManagedTensor::from(arr.slice_mut(s![.., ..;-2, 1..;2]));
+        let t = mt.tensor_mut();
+        assert_eq!(t.shape().unwrap(), vec![2, 2, 2]);
+        assert_eq!(t.strides(), Some(vec![12, -8, 2]));
+        assert_eq!(ArrayViewD::<i32>::try_from(&*t).unwrap(), expected);
+        ArrayViewMutD::<i32>::try_from(t).unwrap()[[1, 0, 1]] = -1;
+        drop(mt);
+        assert_eq!(arr[[1, 2, 3]], -1);
+
+        let t = ManagedTensorVersioned::from(arr.view().into_shape(IxDyn(&[4, 6])).unwrap());
+        assert!(matches!(
+            ArrayViewD::<f32>::try_from(t.tensor()),
+            Err(TensorAccessError::DataTypeMismatch { .. })
+        ));
+        let empty = Array::<f64, _>::zeros((0, 3));
+        let t = ManagedTensorVersioned::from(empty.view());
+        assert_eq!(
+            ArrayViewD::<f64>::try_from(t.tensor()).unwrap().shape(),
+            &[0, 3]
+        );
+    }
+
+    #[test]
+    fn owned_array() {
+        let mut arr = Array::from_shape_vec((2, 3), (0..6).collect::<Vec<i32>>()).unwrap();
+        arr.invert_axis(Axis(1));
+        let ptr = arr.as_ptr();
+        let mt = ManagedTensor::from_ndarray(arr);
+        assert_eq!(mt.tensor().strides(), Some(vec![3, -1]));
+        assert_eq!(mt.tensor().byte_offset(), 8);
+        let raw = mt.into_raw();
+        let mt = unsafe { ManagedTensor::<f32>::from_raw(raw) };
+        let raw = mt.into_ndarray().unwrap_err().into_raw();
+        let arr = unsafe { ManagedTensor::<i32>::from_raw(raw) }
+            .into_ndarray()
+            .unwrap();
+        assert_eq!(arr.as_ptr(), ptr);
+        assert_eq!(arr, array![[2, 1, 0], [5, 4, 3]].into_dyn());
+
+        let mt = ManagedTensorVersioned::from_vec(vec![0u8; 3], &[3]).unwrap();
+        assert!(mt.into_ndarray().is_err());
+        let mt = ManagedTensorVersioned::from_ndarray(array![true, false]);
+        assert_eq!(mt.into_ndarray().unwrap(), array![true, false].into_dyn());
+    }
+
+    #[test]
+    fn overlapping_entries() {
+        let arr = array![1u8, 2, 3];
+        let mut data = arr.clone();
+        let mut shape = [2_i64, 3];
+        // broadcasting the same row twice
+        let mut strides = [0_i64, 1];
+        let mut t = unsafe {
+            Tensor::new(
+                data.as_mut_ptr() as *mut c_void,
+                Device::default(),
+                2,
+                crate::DataType::u8(),
+                shape.as_mut_ptr(),
+                strides.as_mut_ptr(),
+                0,
+            )
+        };
+        let view = ArrayViewD::<u8>::try_from(&t).unwrap();
+        assert_eq!(view, arr.broadcast((2, 3)).unwrap().into_dyn());
+        assert!(matches!(
+            ArrayViewMutD::<u8>::try_from(&mut t),
+            Err(TensorAccessError::Overlapping)
+        ));
+    }
+}
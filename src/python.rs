@@ -0,0 +1,289 @@
+//! Python [DLPack protocol](https://dmlc.github.io/dlpack/latest/python_spec.html) support through
+//! [PyO3](https://docs.rs/pyo3) capsules.
+//!
+//! A tensor is handed to Python in a capsule named `"dltensor"` (or `"dltensor_versioned"` for
+//! DLPack 1.x), which a consumer renames to `"used_dltensor"` (or `"used_dltensor_versioned"`) once
+//! it takes over the tensor. The destructor of the capsule calls the deleter of the tensor only if
+//! the capsule has not been consumed.
+//!
+//! ## Example
+//!
+//! A `#[pyclass]` implements `__dlpack__` and `__dlpack_device__` with [`dlpack`] and
+//! [`dlpack_device`].
+//!
+//! ```no_run
+//! use dlpackrs::{python, Device, ManagedTensorVersioned};
+//! use pyo3::{prelude::*, types::PyCapsule};
+//!
+//! #[pyclass]
+//! struct Array {
+//!     data: Vec<f32>,
+//! }
+//!
+//! #[pymethods]
+//! impl Array {
+//!     #[pyo3(signature = (*, stream=None, max_version=None, dl_device=None, copy=None))]
+//!     fn __dlpack__<'py>(
+//!         &self,
+//!         py: Python<'py>,
+//!         stream: Option<PyObject>,
+//!         max_version: Option<(u32, u32)>,
+//!         dl_device: Option<(i32, i32)>,
+//!         copy: Option<bool>,
+//!     ) -> PyResult<Bound<'py, PyCapsule>> {
+//!         let len = self.data.len();
+//!         let mt = ManagedTensorVersioned::from_vec(self.data.clone(), &[len]).unwrap();
+//!         python::dlpack(py, mt, max_version)
+//!     }
+//!
+//!     fn __dlpack_device__(&self) -> (i32, i32) {
+//!         python::dlpack_device(Device::cpu(0))
+//!     }
+//! }
+//! ```
+
+use std::os::raw::{c_char, c_void};
+
+use pyo3::{
+    exceptions::{PyBufferError, PyValueError},
+    ffi,
+    prelude::*,
+    types::PyCapsule,
+};
+
+use crate::{
+    device::Device,
+    ffi::{DLManagedTensor, DLManagedTensorVersioned},
+    tensor::{ManagedTensor, ManagedTensorVersioned},
+};
+
+const DLTENSOR: &[u8] = b"dltensor\0";
+const USED_DLTENSOR: &[u8] = b"used_dltensor\0";
+const DLTENSOR_VERSIONED: &[u8] = b"dltensor_versioned\0";
+const USED_DLTENSOR_VERSIONED: &[u8] = b"used_dltensor_versioned\0";
+
+/// Returns the capsule name as a C string pointer.
+fn name(name: &'static [u8]) -> *const c_char {
+    name.as_ptr() as *const c_char
+}
+
+/// Capsule destructor calling the deleter of the DLManagedTensor unless the capsule has been
+/// consumed, i.e. renamed.
+unsafe extern "C" fn delete_dltensor_capsule(capsule: *mut ffi::PyObject) {
+    if ffi::PyCapsule_IsValid(capsule, name(DLTENSOR)) == 1 {
+        let ptr = ffi::PyCapsule_GetPointer(capsule, name(DLTENSOR)) as *mut DLManagedTensor;
+        if let Some(deleter) = (*ptr).deleter {
+            deleter(ptr);
+        }
+    }
+}
+
+/// Same as `delete_dltensor_capsule` for the versioned layout.
+unsafe extern "C" fn delete_dltensor_versioned_capsule(capsule: *mut ffi::PyObject) {
+    if ffi::PyCapsule_IsValid(capsule, name(DLTENSOR_VERSIONED)) == 1 {
+        let ptr = ffi::PyCapsule_GetPointer(capsule, name(DLTENSOR_VERSIONED))
+            as *mut DLManagedTensorVersioned;
+        if let Some(deleter) = (*ptr).deleter {
+            deleter(ptr);
+        }
+    }
+}
+
+/// Wraps the pointer into a new capsule with the given name and destructor.
+unsafe fn new_capsule<'py>(
+    py: Python<'py>,
+    ptr: *mut c_void,
+    capsule_name: &'static [u8],
+    destructor: unsafe extern "C" fn(*mut ffi::PyObject),
+) -> PyResult<Bound<'py, PyCapsule>> {
+    let capsule = ffi::PyCapsule_New(ptr, name(capsule_name), Some(destructor));
+    Bound::from_owned_ptr_or_err(py, capsule).map(|capsule| capsule.downcast_into_unchecked())
+}
+
+/// Returns the pointer held by a capsule with the given name and renames it to `used_name`, so that
+/// neither its destructor nor another consumer releases the tensor.
+fn consume_capsule(
+    capsule: &Bound<'_, PyCapsule>,
+    capsule_name: &'static [u8],
+    used_name: &'static [u8],
+) -> PyResult<*mut c_void> {
+    let raw = capsule.as_ptr();
+    unsafe {
+        if ffi::PyCapsule_IsValid(raw, name(capsule_name)) != 1 {
+            return Err(PyValueError::new_err(format!(
+                "expected an unconsumed capsule named {:?}",
+                String::from_utf8_lossy(&capsule_name[..capsule_name.len() - 1]),
+            )));
+        }
+        let ptr = ffi::PyCapsule_GetPointer(raw, name(capsule_name));
+        if ptr.is_null() {
+            return Err(PyErr::fetch(capsule.py()));
+        }
+        if ffi::PyCapsule_SetName(raw, name(used_name)) != 0 {
+            return Err(PyErr::fetch(capsule.py()));
+        }
+        Ok(ptr)
+    }
+}
+
+impl<C: 'static> ManagedTensor<'static, C> {
+    /// Moves the ManagedTensor into a `"dltensor"` capsule, whose destructor calls the deleter
+    /// unless a consumer has taken over the tensor.
+    pub fn into_pycapsule(self, py: Python<'_>) -> PyResult<Bound<'_, PyCapsule>> {
+        let ptr = self.into_raw();
+        unsafe {
+            new_capsule(py, ptr as *mut c_void, DLTENSOR, delete_dltensor_capsule).inspect_err(
+                |_| {
+                    drop(Self::from_raw(ptr));
+                },
+            )
+        }
+    }
+}
+
+impl<'tensor, C: 'tensor> ManagedTensor<'tensor, C> {
+    /// Takes over the tensor of a `"dltensor"` capsule and renames the capsule to
+    /// `"used_dltensor"`, so that it can not be consumed twice.
+    /// Tensors from foreign producers should be checked with [`crate::Tensor::validate`] before use.
+    pub fn from_pycapsule(capsule: &Bound<'_, PyCapsule>) -> PyResult<Self> {
+        let ptr = consume_capsule(capsule, DLTENSOR, USED_DLTENSOR)?;
+        Ok(unsafe { Self::from_raw(ptr as *mut DLManagedTensor) })
+    }
+}
+
+impl<C: 'static> ManagedTensorVersioned<'static, C> {
+    /// Same as [`ManagedTensor::into_pycapsule`] with a `"dltensor_versioned"` capsule.
+    pub fn into_pycapsule(self, py: Python<'_>) -> PyResult<Bound<'_, PyCapsule>> {
+        let ptr = self.into_raw();
+        unsafe {
+            new_capsule(
+                py,
+                ptr as *mut c_void,
+                DLTENSOR_VERSIONED,
+                delete_dltensor_versioned_capsule,
+            )
+            .inspect_err(|_| {
+                drop(Self::from_raw(ptr));
+            })
+        }
+    }
+}
+
+impl<'tensor, C: 'tensor> ManagedTensorVersioned<'tensor, C> {
+    /// Same as [`ManagedTensor::from_pycapsule`] with a `"dltensor_versioned"` capsule, which is
+    /// renamed to `"used_dltensor_versioned"`.
+    ///
+    /// Fails if the tensor has been produced with an incompatible major version of DLPack, in which
+    /// case the tensor is released right away.
+    pub fn from_pycapsule(capsule: &Bound<'_, PyCapsule>) -> PyResult<Self> {
+        let ptr = consume_capsule(capsule, DLTENSOR_VERSIONED, USED_DLTENSOR_VERSIONED)?;
+        unsafe { Self::from_raw(ptr as *mut DLManagedTensorVersioned) }
+            .map_err(|e| PyValueError::new_err(e.to_string()))
+    }
+}
+
+/// Returns the capsule to be returned by `__dlpack__`, given the `max_version` it has been called
+/// with. Consumers supporting DLPack 1.x get a `"dltensor_versioned"` capsule and older ones a
+/// `"dltensor"` capsule, which fails with `BufferError` for read-only tensors since such consumers
+/// can not tell that they must not write to them.
+pub fn dlpack<'py, C: 'static>(
+    py: Python<'py>,
+    tensor: ManagedTensorVersioned<'static, C>,
+    max_version: Option<(u32, u32)>,
+) -> PyResult<Bound<'py, PyCapsule>> {
+    match max_version {
+        Some((major, _)) if major >= 1 => tensor.into_pycapsule(py),
+        _ if tensor.is_read_only() => Err(PyBufferError::new_err(
+            "read-only tensors can not be exported to DLPack 0.x consumers",
+        )),
+        _ => {
+            let t = tensor.tensor();
+            let strides = t
+                .effective_strides()
+                .map_err(|e| PyValueError::new_err(e.to_string()))?
+                .into_owned();
+            let shape = t.shape_i64().unwrap_or_default().to_vec();
+            let device = t
+                .device()
+                .map_err(|e| PyValueError::new_err(e.to_string()))?;
+            let (data, dtype, byte_offset) = (t.data(), t.dtype(), t.inner.byte_offset);
+            ManagedTensor::<C>::from_owner(tensor, data, device, dtype, shape, strides, byte_offset)
+                .into_pycapsule(py)
+        }
+    }
+}
+
+/// Returns the `(device_type, device_id)` tuple to be returned by `__dlpack_device__`.
+pub fn dlpack_device(device: Device) -> (i32, i32) {
+    (device.device_type.0 as i32, device.device_id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Tensor, TensorFlags};
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
+
+    #[test]
+    fn capsule_round_trip() {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let mt = ManagedTensor::<f32>::from_vec(vec![1., 2., 3.], &[3]).unwrap();
+            let capsule = mt.into_pycapsule(py).unwrap();
+            let mt = ManagedTensor::<f32>::from_pycapsule(&capsule).unwrap();
+            assert_eq!(mt.tensor().as_slice::<f32>().unwrap(), &[1., 2., 3.]);
+            assert_eq!(
+                capsule.name().unwrap().unwrap().to_str(),
+                Ok("used_dltensor")
+            );
+            assert!(ManagedTensor::<f32>::from_pycapsule(&capsule).is_err());
+
+            let mt = ManagedTensorVersioned::<u8>::from_vec(vec![1, 2], &[2]).unwrap();
+            let capsule = dlpack(py, mt, Some((1, 0))).unwrap();
+            assert!(ManagedTensor::<u8>::from_pycapsule(&capsule).is_err());
+            let mt = ManagedTensorVersioned::<u8>::from_pycapsule(&capsule).unwrap();
+            assert_eq!(mt.tensor().as_slice::<u8>().unwrap(), &[1, 2]);
+
+            let capsule = dlpack(py, mt, None).unwrap();
+            let mt = ManagedTensor::<u8>::from_pycapsule(&capsule).unwrap();
+            assert_eq!(mt.tensor().as_slice::<u8>().unwrap(), &[1, 2]);
+
+            let mt = ManagedTensorVersioned::<u8>::from_vec(vec![1, 2], &[2])
+                .unwrap()
+                .with_flags(TensorFlags::READ_ONLY);
+            let err = dlpack(py, mt, Some((0, 8))).unwrap_err();
+            assert!(err.is_instance_of::<PyBufferError>(py));
+        });
+    }
+
+    #[test]
+    fn capsule_destructor() {
+        pyo3::prepare_freethreaded_python();
+        let deleted = Arc::new(AtomicUsize::new(0));
+        Python::with_gil(|py| {
+            let counter = deleted.clone();
+            let tensor = unsafe { Tensor::from_inner(crate::ffi::DLTensor::default()) };
+            let mt = ManagedTensor::<f32>::with_deleter(tensor, move |_| {
+                counter.fetch_add(1, Ordering::SeqCst);
+            });
+            drop(mt.into_pycapsule(py).unwrap());
+            assert_eq!(deleted.load(Ordering::SeqCst), 1);
+
+            let counter = deleted.clone();
+            let tensor = unsafe { Tensor::from_inner(crate::ffi::DLTensor::default()) };
+            let mt = ManagedTensorVersioned::<f32>::with_deleter(tensor, move |_| {
+                counter.fetch_add(1, Ordering::SeqCst);
+            });
+            let capsule = mt.into_pycapsule(py).unwrap();
+            let mt = ManagedTensorVersioned::<f32>::from_pycapsule(&capsule).unwrap();
+            drop(capsule);
+            assert_eq!(deleted.load(Ordering::SeqCst), 1);
+            drop(mt);
+            assert_eq!(deleted.load(Ordering::SeqCst), 2);
+        });
+        assert_eq!(dlpack_device(Device::cuda(1)), (2, 1));
+    }
+}
@@ -1,19 +1,31 @@
-use pin_project::{pin_project, pinned_drop};
+use pin_project::pin_project;
 
 use core::slice;
 use std::{
+    any::Any,
+    borrow::Cow,
+    convert::TryFrom,
     fmt::Debug,
     marker::{PhantomData, PhantomPinned},
-    mem::transmute,
+    mem,
+    ops::{BitOr, BitOrAssign, Deref},
     os::raw::c_void,
+    panic::{self, AssertUnwindSafe},
     pin::Pin,
+    process,
     ptr::{self, NonNull},
 };
 
 use crate::{
-    datatype::DataType,
-    device::Device,
-    ffi::{DLManagedTensor, DLTensor},
+    datatype::{DataType, DataTypeCode, Element, PackedElement},
+    device::{Device, DeviceType},
+    errors::{
+        ShapeMismatchError, TensorAccessError, UnsupportedDeviceError, UnsupportedVersionError,
+        ValidationError,
+    },
+    ffi::{
+        self, DLDataTypeCode, DLManagedTensor, DLManagedTensorVersioned, DLPackVersion, DLTensor,
+    },
 };
 
 /// Non-owned Tensor type interface.
@@ -21,7 +33,7 @@ use crate::{
 #[derive(Debug)]
 #[repr(transparent)]
 pub struct Tensor<'tensor> {
-    pub inner: DLTensor,
+    pub(crate) inner: DLTensor,
     _marker: PhantomData<fn(&'tensor ()) -> &'tensor ()>, // invariant wrt 'tensor
 }
 
@@ -31,18 +43,20 @@ impl<'tensor> From<Tensor<'tensor>> for DLTensor {
     }
 }
 
-impl<'tensor> From<DLTensor> for Tensor<'tensor> {
-    fn from(dts: DLTensor) -> Self {
-        Tensor {
-            inner: dts,
-            _marker: PhantomData,
-        }
-    }
-}
+const _: () = assert!(mem::size_of::<Tensor>() == mem::size_of::<DLTensor>());
+const _: () = assert!(mem::align_of::<Tensor>() == mem::align_of::<DLTensor>());
 
 impl<'tensor> Tensor<'tensor> {
-    /// Constructor
-    pub fn new(
+    /// Creates a Tensor from its raw parts.
+    ///
+    /// # Safety
+    ///
+    /// `shape` must point to `ndim` sizes and `strides` must be null or point to `ndim` strides,
+    /// both valid for `'tensor`. `data` must be valid for reads of every entry the shape, strides
+    /// and byte offset describe for `'tensor`, and for writes if the Tensor is used mutably, and
+    /// must not be written to by anyone else meanwhile. The accessors only check the data type,
+    /// alignment and layout of the entries, and [`Tensor::validate`] their description.
+    pub unsafe fn new(
         data: *mut c_void,
         device: Device,
         ndim: i32,
@@ -66,6 +80,53 @@ impl<'tensor> Tensor<'tensor> {
         }
     }
 
+    /// Creates a Tensor from a DLTensor, e.g. one handed over by a foreign producer.
+    ///
+    /// # Safety
+    ///
+    /// Same as [`Tensor::new`] for the fields of the DLTensor.
+    pub unsafe fn from_inner(dlt: DLTensor) -> Self {
+        Tensor {
+            inner: dlt,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Reinterprets a DLTensor in place, whose data is borrowed as long as the DLTensor.
+    /// Tensors from foreign producers should be checked with [`Tensor::validate`] before use.
+    ///
+    /// # Safety
+    ///
+    /// Same as [`Tensor::new`] for the fields of the DLTensor, whose data must not be written to
+    /// meanwhile.
+    pub unsafe fn from_raw_ref<'a>(dlt: &'a DLTensor) -> &'a Tensor<'tensor> {
+        &*(dlt as *const DLTensor as *const Tensor)
+    }
+
+    /// Same as [`Tensor::from_raw_ref`] for mutable references.
+    ///
+    /// # Safety
+    ///
+    /// Same as [`Tensor::new`] for the fields of the DLTensor, including that its data is valid
+    /// for writes, e.g. it must not belong to a read-only tensor.
+    pub unsafe fn from_raw_mut<'a>(dlt: &'a mut DLTensor) -> &'a mut Tensor<'tensor> {
+        &mut *(dlt as *mut DLTensor as *mut Tensor)
+    }
+
+    /// Returns the underlying DLTensor.
+    pub fn as_dltensor(&self) -> &DLTensor {
+        &self.inner
+    }
+
+    /// Returns the underlying DLTensor mutably.
+    ///
+    /// # Safety
+    ///
+    /// The DLTensor must keep satisfying the contract of [`Tensor::new`] for `'tensor`.
+    pub unsafe fn as_dltensor_mut(&mut self) -> &mut DLTensor {
+        &mut self.inner
+    }
+
     /// Returns the underlying DLTensor where lifetime parameter is removed.
     pub fn into_inner(self) -> DLTensor {
         self.inner
@@ -77,6 +138,7 @@ impl<'tensor> Tensor<'tensor> {
     }
 
     /// Creates a Tensor from a raw DLTensor pointer (must be non-null).
+    /// Tensors from foreign producers should be checked with [`Tensor::validate`] before use.
     pub unsafe fn from_raw(ptr: *mut DLTensor) -> Self {
         debug_assert!(!ptr.is_null());
         Tensor {
@@ -90,15 +152,27 @@ impl<'tensor> Tensor<'tensor> {
         self.inner.data
     }
 
-    /// Returns the device type.
-    pub fn device(&self) -> Device {
-        self.inner.device.into()
+    /// Returns the device of the data, failing if the device id is negative.
+    pub fn device(&self) -> Result<Device, UnsupportedDeviceError> {
+        Device::try_from(self.inner.device)
     }
 
-    /// Returns the size of an entry/item in the Tensor.
+    /// Returns the size (in bytes) of an entry/item in the Tensor, rounded up for packed entries of
+    /// less than a byte.
     pub fn itemsize(&self) -> usize {
+        self.item_bits().div_ceil(8)
+    }
+
+    /// Returns the size (in bits) of an entry/item in the Tensor, i.e. of all its lanes.
+    pub fn item_bits(&self) -> usize {
         let ty = self.dtype();
-        ty.lanes() * ty.bits() / 8_usize
+        ty.lanes() * ty.bits()
+    }
+
+    /// Returns whether the entries take less than a byte, in which case they are packed densely
+    /// from the least significant bit of each byte.
+    pub fn is_packed(&self) -> bool {
+        self.item_bits() < 8
     }
 
     /// Returns the number of dimensions of the Tensor.
@@ -111,24 +185,34 @@ impl<'tensor> Tensor<'tensor> {
         self.inner.dtype.into()
     }
 
-    /// Returns the shape of the Tensor.
-    pub fn shape(&self) -> Option<&[usize]> {
-        let dlt = self.inner;
-        if dlt.shape.is_null() || dlt.data.is_null() {
-            return None;
-        };
-        let ret = unsafe { slice::from_raw_parts(dlt.shape as *const _, dlt.ndim as usize) };
-        Some(ret)
+    /// Returns the shape of the Tensor as it is stored, i.e. without checking for negative sizes.
+    /// Returns `None` when the shape is null while the Tensor has dimensions.
+    pub fn shape_i64(&self) -> Option<&[i64]> {
+        unsafe { dims(self.inner.shape, self.inner.ndim) }
+    }
+
+    /// Returns the shape of the Tensor, failing if it is null or holds negative sizes.
+    pub fn shape(&self) -> Result<Vec<usize>, ValidationError> {
+        Ok(self
+            .checked_shape()?
+            .iter()
+            .map(|&size| size as usize)
+            .collect())
     }
 
-    /// Returns the strides of the underlying Tensor.
-    pub fn strides(&self) -> Option<&[usize]> {
-        let dlt = self.inner;
-        if dlt.strides.is_null() || dlt.data.is_null() {
+    /// Returns the strides (in number of elements) of the Tensor as they are stored, possibly
+    /// negative. Returns `None` for null strides, which stand for the compact row-major ones.
+    pub fn strides_i64(&self) -> Option<&[i64]> {
+        if self.inner.strides.is_null() {
             return None;
-        };
-        let ret = unsafe { slice::from_raw_parts(dlt.strides as *const _, dlt.ndim as usize) };
-        Some(ret)
+        }
+        unsafe { dims(self.inner.strides, self.inner.ndim) }
+    }
+
+    /// Returns the strides (in number of elements) of the underlying Tensor, possibly negative.
+    pub fn strides(&self) -> Option<Vec<isize>> {
+        self.strides_i64()
+            .map(|strides| strides.iter().map(|&stride| stride as isize).collect())
     }
 
     /// Returns the byte offset of the underlying Tensor.
@@ -136,26 +220,684 @@ impl<'tensor> Tensor<'tensor> {
         self.inner.byte_offset as isize
     }
 
-    /// Returns the size of the memory required to store the underlying data of the Tensor.
+    /// Returns the number of entries of the Tensor.
+    pub fn numel(&self) -> Result<usize, ValidationError> {
+        self.checked_shape()?
+            .iter()
+            .try_fold(1_usize, |acc, &size| acc.checked_mul(size as usize))
+            .ok_or(ValidationError::ElementCountOverflow)
+    }
+
+    /// Returns the size of the memory required to store the underlying data of the Tensor, i.e. the
+    /// number of bytes spanned by its strides.
     pub fn size(&self) -> Option<usize> {
+        if self.numel().ok()? == 0 {
+            return Some(0);
+        }
+        let (low, high) = self.offset_range().ok()?;
         let ty = self.dtype();
-        self.shape().map(|v| {
-            v.iter().product::<usize>() * (ty.bits() as usize * ty.lanes() as usize + 7) / 8
+        let bits = (high - low + 1).checked_mul(ty.bits() as i128 * ty.lanes() as i128)?;
+        usize::try_from((bits + 7) / 8).ok()
+    }
+
+    /// Returns the offset (in number of elements) of the entry at the given index from the first
+    /// entry of the data, which is negative when reached through negative strides.
+    /// Returns `None` if the index is out of bounds.
+    pub fn element_offset(&self, index: &[usize]) -> Option<i64> {
+        let shape = self.checked_shape().ok()?;
+        if index.len() != shape.len() {
+            return None;
+        }
+        let strides = self.effective_strides().ok()?;
+        index.iter().zip(shape).zip(strides.iter()).try_fold(
+            0_i64,
+            |offset, ((&i, &size), &stride)| {
+                let i = i64::try_from(i).ok().filter(|&i| i < size)?;
+                offset.checked_add(i.checked_mul(stride)?)
+            },
+        )
+    }
+
+    /// Returns the strides (in number of elements) of the Tensor, where null strides are replaced by
+    /// the compact row-major ones they stand for.
+    pub fn effective_strides(&self) -> Result<Cow<'_, [i64]>, ValidationError> {
+        let shape = self.checked_shape()?;
+        Ok(match self.strides_i64() {
+            Some(strides) => Cow::Borrowed(strides),
+            None => Cow::Owned(row_major_strides(shape)?),
+        })
+    }
+
+    /// Returns whether the entries are laid out in compact row-major (C) order.
+    /// Strides of size-1 dimensions are ignored and empty tensors are contiguous.
+    pub fn is_c_contiguous(&self) -> bool {
+        match (self.checked_shape(), self.strides_i64()) {
+            (Ok(_), None) => true,
+            (Ok(shape), Some(strides)) => {
+                shape.contains(&0) || is_contiguous(shape.iter().zip(strides).rev())
+            }
+            (Err(_), _) => false,
+        }
+    }
+
+    /// Returns whether the entries are laid out in compact column-major (Fortran) order.
+    /// Strides of size-1 dimensions are ignored and empty tensors are contiguous.
+    pub fn is_f_contiguous(&self) -> bool {
+        match (self.checked_shape(), self.effective_strides()) {
+            (Ok(shape), Ok(strides)) => {
+                shape.contains(&0) || is_contiguous(shape.iter().zip(strides.iter()))
+            }
+            _ => false,
+        }
+    }
+
+    /// Returns whether the entries occupy a contiguous block of memory without gaps or overlaps,
+    /// in any order and direction of the dimensions, e.g. with negative strides. Strides of size-1
+    /// dimensions are ignored.
+    pub fn is_compact(&self) -> bool {
+        let (shape, strides) = match (self.checked_shape(), self.effective_strides()) {
+            (Ok(shape), Ok(strides)) => (shape, strides),
+            _ => return false,
+        };
+        if shape.contains(&0) {
+            return true;
+        }
+        let strides = match strides
+            .iter()
+            .map(|stride| stride.checked_abs())
+            .collect::<Option<Vec<_>>>()
+        {
+            Some(strides) => strides,
+            None => return false,
+        };
+        let mut dims = shape.iter().zip(strides.iter()).collect::<Vec<_>>();
+        dims.sort_by_key(|&(_, &stride)| stride);
+        is_contiguous(dims.into_iter())
+    }
+
+    /// Returns whether distinct indices address distinct entries, which is conservatively decided by
+    /// requiring every stride to step over all the entries spanned by the dimensions of smaller
+    /// strides. Broadcast tensors, i.e. with zero strides, do overlap.
+    pub fn is_non_overlapping(&self) -> bool {
+        let (shape, strides) = match (self.checked_shape(), self.effective_strides()) {
+            (Ok(shape), Ok(strides)) => (shape, strides),
+            _ => return false,
+        };
+        if shape.contains(&0) {
+            return true;
+        }
+        let mut dims = shape
+            .iter()
+            .zip(strides.iter())
+            .filter(|(&size, _)| size != 1)
+            .map(|(&size, &stride)| (size as i128, (stride as i128).abs()))
+            .collect::<Vec<_>>();
+        dims.sort_by_key(|&(_, stride)| stride);
+        let mut extent = 1;
+        for (size, stride) in dims {
+            if stride < extent {
+                return false;
+            }
+            extent += (size - 1) * stride;
+        }
+        true
+    }
+
+    /// Returns the data type, shape and strides (in number of elements) of the complex Tensor seen
+    /// as a real one, with a trailing dimension of size 2 holding the real and imaginary parts.
+    fn real_layout(&self) -> Result<Layout, TensorAccessError> {
+        let dtype = self.dtype();
+        if dtype.code != DataTypeCode::Complex as u8 || dtype.lanes != 1 || dtype.bits % 16 != 0 {
+            return Err(TensorAccessError::IncompatibleDataType(dtype));
+        }
+        self.validate()?;
+        let mut shape = self.checked_shape()?.to_vec();
+        let strides = self.effective_strides()?;
+        let mut real_strides = strides
+            .iter()
+            .map(|&stride| stride.checked_mul(2))
+            .collect::<Option<Vec<_>>>()
+            .ok_or_else(|| TensorAccessError::IncompatibleStrides(strides.to_vec()))?;
+        shape.push(2);
+        real_strides.push(1);
+        let device = self.device().map_err(ValidationError::from)?;
+        Ok((
+            device,
+            DataType::float(dtype.bits / 2, 1),
+            shape,
+            real_strides,
+        ))
+    }
+
+    /// Returns the data type, shape and strides (in number of elements) of the real Tensor seen as
+    /// a complex one, which requires a trailing dimension of size 2 and stride 1 and even strides
+    /// otherwise.
+    fn complex_layout(&self) -> Result<Layout, TensorAccessError> {
+        let dtype = self.dtype();
+        if dtype.code != DataTypeCode::Float as u8 || dtype.lanes != 1 || dtype.bits > 64 {
+            return Err(TensorAccessError::IncompatibleDataType(dtype));
+        }
+        self.validate()?;
+        let shape = self.checked_shape()?;
+        let strides = self.effective_strides()?;
+        if shape.last() != Some(&2) {
+            return Err(TensorAccessError::IncompatibleShape(shape.to_vec()));
+        }
+        let ndim = shape.len() - 1;
+        let is_compatible = strides[ndim] == 1
+            && shape[..ndim]
+                .iter()
+                .zip(strides.iter())
+                .all(|(&size, &stride)| size <= 1 || stride % 2 == 0);
+        if !is_compatible {
+            return Err(TensorAccessError::IncompatibleStrides(strides.into_owned()));
+        }
+        Ok((
+            self.device().map_err(ValidationError::from)?,
+            DataType::complex(dtype.bits * 2, 1),
+            shape[..ndim].to_vec(),
+            strides[..ndim].iter().map(|&stride| stride / 2).collect(),
+        ))
+    }
+
+    /// Returns the shape, failing if it is null or holds negative sizes.
+    fn checked_shape(&self) -> Result<&[i64], ValidationError> {
+        if self.inner.ndim < 0 {
+            return Err(ValidationError::NegativeNdim(self.inner.ndim));
+        }
+        let shape = self
+            .shape_i64()
+            .ok_or(ValidationError::NullShape(self.inner.ndim))?;
+        match shape.iter().enumerate().find(|(_, &size)| size < 0) {
+            Some((dim, &size)) => Err(ValidationError::NegativeShape { dim, size }),
+            None => Ok(shape),
+        }
+    }
+
+    /// Returns the offsets (in number of elements) of the lowest and highest addressed entries of a
+    /// non-empty Tensor relative to its first entry.
+    pub(crate) fn offset_range(&self) -> Result<(i128, i128), ValidationError> {
+        let shape = self.checked_shape()?;
+        Ok(match self.strides_i64() {
+            None => (
+                0,
+                shape.iter().map(|&size| size as i128).product::<i128>() - 1,
+            ),
+            Some(strides) => {
+                shape
+                    .iter()
+                    .zip(strides)
+                    .fold((0, 0), |(low, high), (&size, &stride)| {
+                        let reach = (size as i128 - 1) * stride as i128;
+                        (low + reach.min(0), high + reach.max(0))
+                    })
+            }
         })
     }
+
+    /// Checks that the Tensor describes a valid DLPack tensor, which is useful before reading tensors
+    /// handed over by foreign producers.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use dlpackrs::{errors::ValidationError, ffi::DLTensor, Tensor};
+    /// let mut dlt = DLTensor::default();
+    /// dlt.device.device_type = 1;
+    /// dlt.dtype.bits = 32;
+    /// dlt.dtype.lanes = 1;
+    /// dlt.ndim = 1;
+    /// let t = unsafe { Tensor::from_inner(dlt) };
+    /// assert!(matches!(t.validate(), Err(ValidationError::NullShape(1))));
+    /// ```
+    pub fn validate(&self) -> Result<(), ValidationError> {
+        let dlt = &self.inner;
+        let device = Device::try_from(dlt.device)?;
+        DataTypeCode::try_from(dlt.dtype.code as DLDataTypeCode)?;
+        if dlt.dtype.bits == 0 || dlt.dtype.lanes == 0 {
+            return Err(ValidationError::InvalidDataType(dlt.dtype.into()));
+        }
+        let numel = self.numel()?;
+        if numel == 0 {
+            return Ok(());
+        }
+        if dlt.data.is_null() {
+            return Err(ValidationError::NullData(numel));
+        }
+        let item_bits = dlt.dtype.bits as i128 * dlt.dtype.lanes as i128;
+        let (low, high) = self.offset_range()?;
+        let byte_offset = dlt.byte_offset as i128;
+        let low_bits = low
+            .checked_mul(item_bits)
+            .and_then(|bits| bits.checked_add(byte_offset * 8))
+            .ok_or(ValidationError::ByteSizeOverflow)?;
+        if low_bits < 0 {
+            return Err(ValidationError::StridesOutOfBounds);
+        }
+        let high_bits = (high + 1)
+            .checked_mul(item_bits)
+            .and_then(|bits| bits.checked_add(7))
+            .ok_or(ValidationError::ByteSizeOverflow)?;
+        if byte_offset + high_bits / 8 > isize::MAX as i128 {
+            return Err(ValidationError::ByteSizeOverflow);
+        }
+        // the data of these devices, and possibly of unknown ones, is an opaque handle rather than an
+        // address
+        let is_address = device.device_type.is_known()
+            && !matches!(
+                device.device_type,
+                DeviceType::OpenCL | DeviceType::Vulkan | DeviceType::Metal
+            );
+        // complex entries are pairs of scalars aligned as such
+        let scalar_bytes = if dlt.dtype.code == DataTypeCode::Complex as u8 {
+            dlt.dtype.bits as usize / 16
+        } else {
+            dlt.dtype.bits as usize / 8
+        };
+        if is_address && dlt.dtype.bits % 8 == 0 && scalar_bytes.is_power_of_two() {
+            let address = (dlt.data as usize).wrapping_add(dlt.byte_offset as usize);
+            if address % scalar_bytes != 0 {
+                return Err(ValidationError::Misaligned(scalar_bytes));
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the entries of the Tensor as a slice.
+    ///
+    /// Fails unless the entries are of type `T`, are accessible from the host, are laid out in
+    /// compact row-major order and are aligned for `T`.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use dlpackrs::ManagedTensor;
+    /// let mt = ManagedTensor::from_vec(vec![1f32, 2., 3., 4.], &[2, 2]).unwrap();
+    /// assert_eq!(mt.tensor().as_slice::<f32>().unwrap(), &[1., 2., 3., 4.]);
+    /// assert!(mt.tensor().as_slice::<i32>().is_err());
+    /// ```
+    pub fn as_slice<T: Element>(&self) -> Result<&[T], TensorAccessError> {
+        let (ptr, len) = self.checked_data::<T>()?;
+        Ok(unsafe { slice::from_raw_parts(ptr, len) })
+    }
+
+    /// Returns the entries of the Tensor as a mutable slice.
+    /// See [`Tensor::as_slice`] for the conditions under which it fails.
+    pub fn as_slice_mut<T: Element>(&mut self) -> Result<&mut [T], TensorAccessError> {
+        let (ptr, len) = self.checked_data::<T>()?;
+        Ok(unsafe { slice::from_raw_parts_mut(ptr, len) })
+    }
+
+    /// Returns the packed entry at the given index.
+    ///
+    /// Fails unless the entries are packed or take a byte, e.g. float8 entries, unpack to `T`, are
+    /// accessible from the host and the index is within bounds.
+    ///
+    /// Entries of less than a byte are assumed to be packed densely. The entries of a versioned
+    /// tensor which may be padded to a byte each are accessed with
+    /// [`ManagedTensorVersioned::get_packed`] and its siblings instead.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use std::{os::raw::c_void, ptr};
+    /// use dlpackrs::{DataType, Device, Tensor};
+    ///
+    /// // int4 entries 1, -2, 7, -8
+    /// let mut data = [0xe1_u8, 0x87];
+    /// let mut shape = [2_i64, 2];
+    /// let mut t = unsafe { Tensor::new(
+    ///     data.as_mut_ptr() as *mut c_void,
+    ///     Device::default(),
+    ///     2,
+    ///     DataType::int(4, 1),
+    ///     shape.as_mut_ptr(),
+    ///     ptr::null_mut(),
+    ///     0,
+    /// ) };
+    /// assert_eq!(t.get_packed::<i8>(&[0, 1]).unwrap(), -2);
+    /// t.set_packed(&[1, 1], 3_i8).unwrap();
+    /// assert_eq!(t.unpack::<i8>().unwrap(), vec![1, -2, 7, 3]);
+    /// assert!(t.set_packed(&[0, 0], 8_i8).is_err());
+    /// assert_eq!((t.itemsize(), t.size()), (1, Some(2)));
+    /// ```
+    pub fn get_packed<T: PackedElement>(&self, index: &[usize]) -> Result<T, TensorAccessError> {
+        self.get_packed_with(index, false)
+    }
+
+    /// Same as [`Tensor::get_packed`], where entries of less than a byte take a byte each if
+    /// `padded`.
+    pub(crate) fn get_packed_with<T: PackedElement>(
+        &self,
+        index: &[usize],
+        padded: bool,
+    ) -> Result<T, TensorAccessError> {
+        let (width, step) = self.checked_packed::<T>(padded)?;
+        let offset = self
+            .element_offset(index)
+            .ok_or_else(|| TensorAccessError::IndexOutOfBounds(index.to_vec()))?;
+        let bits = unsafe { read_bits(self.inner.data, self.bit_position(offset, step), width) };
+        Ok(T::from_bits(bits, self.dtype()))
+    }
+
+    /// Writes the packed entry at the given index.
+    ///
+    /// See [`Tensor::get_packed`] for the conditions under which it fails, and also fails if the
+    /// value does not fit in the bits of an entry.
+    pub fn set_packed<T: PackedElement>(
+        &mut self,
+        index: &[usize],
+        value: T,
+    ) -> Result<(), TensorAccessError> {
+        self.set_packed_with(index, value, false)
+    }
+
+    /// Same as [`Tensor::set_packed`], where entries of less than a byte take a byte each if
+    /// `padded`.
+    pub(crate) fn set_packed_with<T: PackedElement>(
+        &mut self,
+        index: &[usize],
+        value: T,
+        padded: bool,
+    ) -> Result<(), TensorAccessError> {
+        let (width, step) = self.checked_packed::<T>(padded)?;
+        let offset = self
+            .element_offset(index)
+            .ok_or_else(|| TensorAccessError::IndexOutOfBounds(index.to_vec()))?;
+        let bits = value
+            .to_bits(self.dtype())
+            .ok_or(TensorAccessError::ValueOutOfRange(width))?;
+        unsafe {
+            write_bits(
+                self.inner.data,
+                self.bit_position(offset, step),
+                width,
+                bits,
+            )
+        };
+        Ok(())
+    }
+
+    /// Returns an iterator over the packed entries in row-major order, whatever the strides are.
+    ///
+    /// See [`Tensor::get_packed`] for the conditions under which it fails.
+    pub fn iter_packed<T: PackedElement>(
+        &self,
+    ) -> Result<impl Iterator<Item = T> + '_, TensorAccessError> {
+        self.iter_packed_with(false)
+    }
+
+    /// Same as [`Tensor::iter_packed`], where entries of less than a byte take a byte each if
+    /// `padded`.
+    pub(crate) fn iter_packed_with<T: PackedElement>(
+        &self,
+        padded: bool,
+    ) -> Result<impl Iterator<Item = T> + '_, TensorAccessError> {
+        let (width, step) = self.checked_packed::<T>(padded)?;
+        let numel = self.numel()?;
+        let shape = self.checked_shape()?;
+        let strides = self.effective_strides()?;
+        let data = self.inner.data;
+        let (dtype, first) = (self.dtype(), self.bit_position(0, step));
+        Ok((0..numel).map(move |mut i| {
+            let mut offset = 0;
+            for (&size, &stride) in shape.iter().zip(strides.iter()).rev() {
+                offset += (i % size as usize) as i64 * stride;
+                i /= size as usize;
+            }
+            let bits = unsafe { read_bits(data, first + offset as i128 * step as i128, width) };
+            T::from_bits(bits, dtype)
+        }))
+    }
+
+    /// Returns the packed entries unpacked in row-major order, e.g. int4 entries as `Vec<i8>` or
+    /// float8 entries as `Vec<f32>`.
+    ///
+    /// See [`Tensor::get_packed`] for the conditions under which it fails.
+    pub fn unpack<T: PackedElement>(&self) -> Result<Vec<T>, TensorAccessError> {
+        Ok(self.iter_packed()?.collect())
+    }
+
+    /// Returns the number of bits of the entries if they take at most a byte and can be accessed as
+    /// `T`, along with the number of bits from one entry to the next, i.e. a byte if `padded`.
+    fn checked_packed<T: PackedElement>(
+        &self,
+        padded: bool,
+    ) -> Result<(u8, u8), TensorAccessError> {
+        let dtype = self.dtype();
+        if dtype.lanes != 1 || dtype.bits == 0 || dtype.bits > 8 || !T::accepts(dtype) {
+            return Err(TensorAccessError::IncompatibleDataType(dtype));
+        }
+        self.check_host()?;
+        self.validate()?;
+        Ok((dtype.bits, if padded { 8 } else { dtype.bits }))
+    }
+
+    /// Returns the position (in bits from the data pointer) of the entry at the given offset (in
+    /// number of elements) from the first entry, for entries `step` bits apart.
+    fn bit_position(&self, offset: i64, step: u8) -> i128 {
+        self.inner.byte_offset as i128 * 8 + offset as i128 * step as i128
+    }
+
+    /// Returns the pointer to the first entry and the number of entries if they can be accessed as
+    /// a slice of `T`.
+    fn checked_data<T: Element>(&self) -> Result<(*mut T, usize), TensorAccessError> {
+        let (ptr, shape, _) = self.checked_entries::<T>()?;
+        if !self.is_c_contiguous() {
+            return Err(TensorAccessError::NotContiguous);
+        }
+        let len = shape.iter().map(|&size| size as usize).product();
+        Ok((ptr, len))
+    }
+
+    /// Fails unless the data of the Tensor is accessible from the host.
+    pub(crate) fn check_host(&self) -> Result<(), TensorAccessError> {
+        let device = self.device().map_err(ValidationError::from)?;
+        if !matches!(
+            device.device_type,
+            DeviceType::CPU | DeviceType::CUDAHost | DeviceType::ROCMHost | DeviceType::CUDAManaged
+        ) {
+            return Err(TensorAccessError::NotOnHost(device));
+        }
+        Ok(())
+    }
+
+    /// Returns the pointer to the first entry along with the shape and strides (in number of
+    /// elements) if the entries can be accessed as `T`, in any layout.
+    ///
+    /// The pointer is dangling for empty tensors.
+    pub(crate) fn checked_entries<T: Element>(&self) -> Result<Entries<'_, T>, TensorAccessError> {
+        let dtype = self.dtype();
+        if dtype != T::DTYPE {
+            return Err(TensorAccessError::DataTypeMismatch {
+                expected: T::DTYPE,
+                found: dtype,
+            });
+        }
+        self.check_host()?;
+        self.validate()?;
+        let shape = self.checked_shape()?;
+        let strides = self.effective_strides()?;
+        if shape.contains(&0) {
+            return Ok((NonNull::dangling().as_ptr(), shape, strides));
+        }
+        let ptr =
+            (self.inner.data as *mut u8).wrapping_add(self.inner.byte_offset as usize) as *mut T;
+        if ptr as usize % mem::align_of::<T>() != 0 {
+            return Err(TensorAccessError::Misaligned(mem::align_of::<T>()));
+        }
+        let is_valid = if T::ALL_BITS_VALID {
+            true
+        } else if self.is_compact() {
+            let (low, high) = self.offset_range()?;
+            let start = ptr.wrapping_offset(low as isize) as *const u8;
+            let len = (high - low + 1) as usize * mem::size_of::<T>();
+            T::is_valid(unsafe { slice::from_raw_parts(start, len) })
+        } else {
+            all_offsets(shape, &strides, |offset| {
+                let entry = ptr.wrapping_offset(offset as isize) as *const u8;
+                T::is_valid(unsafe { slice::from_raw_parts(entry, mem::size_of::<T>()) })
+            })
+        };
+        if !is_valid {
+            return Err(TensorAccessError::InvalidValue);
+        }
+        Ok((ptr, shape, strides))
+    }
+}
+
+/// A Tensor borrowed mutably from the owner of its data and dims, e.g. by
+/// [`ManagedTensor::tensor_mut`], [`ManagedTensorVersioned::tensor_mut`] and
+/// [`TensorViewMut::tensor_mut`](crate::TensorViewMut::tensor_mut).
+///
+/// Its entries can be written to, but unlike `&mut Tensor` it can not be replaced as a whole, e.g.
+/// with [`std::mem::swap`], since the owner only keeps alive the data of its own Tensor.
+///
+/// ```compile_fail
+/// use dlpackrs::ManagedTensor;
+/// let mut a = ManagedTensor::from_vec(vec![1u64, 2], &[2]).unwrap();
+/// let mut b = ManagedTensor::from_vec(vec![3u64], &[1]).unwrap();
+/// std::mem::swap(&mut *a.tensor_mut(), &mut *b.tensor_mut());
+/// ```
+#[derive(Debug)]
+pub struct TensorMut<'a, 'tensor>(pub(crate) &'a mut Tensor<'tensor>);
+
+impl<'tensor> Deref for TensorMut<'_, 'tensor> {
+    type Target = Tensor<'tensor>;
+
+    fn deref(&self) -> &Self::Target {
+        self.0
+    }
+}
+
+impl<'a, 'tensor> TensorMut<'a, 'tensor> {
+    /// Same as [`Tensor::as_slice_mut`].
+    pub fn as_slice_mut<T: Element>(&mut self) -> Result<&mut [T], TensorAccessError> {
+        self.0.as_slice_mut()
+    }
+
+    /// Same as [`Tensor::as_slice_mut`], borrowing the entries as long as the Tensor.
+    pub fn into_slice_mut<T: Element>(self) -> Result<&'a mut [T], TensorAccessError> {
+        self.0.as_slice_mut()
+    }
+
+    /// Same as [`Tensor::set_packed`].
+    pub fn set_packed<T: PackedElement>(
+        &mut self,
+        index: &[usize],
+        value: T,
+    ) -> Result<(), TensorAccessError> {
+        self.0.set_packed(index, value)
+    }
+
+    /// Same as [`Tensor::as_dltensor_mut`].
+    ///
+    /// # Safety
+    ///
+    /// Same as [`Tensor::as_dltensor_mut`].
+    pub unsafe fn as_dltensor_mut(&mut self) -> &mut DLTensor {
+        self.0.as_dltensor_mut()
+    }
+}
+
+/// Returns the `width` bits of the packed entry at the given position (in bits) from `data`, which
+/// must be within the data of a valid tensor.
+unsafe fn read_bits(data: *mut c_void, bit: i128, width: u8) -> u8 {
+    let (ptr, shift) = (
+        (data as *const u8).add((bit / 8) as usize),
+        (bit % 8) as u32,
+    );
+    let mut word = *ptr as u16;
+    if shift + width as u32 > 8 {
+        word |= (*ptr.add(1) as u16) << 8;
+    }
+    ((word >> shift) & ((1 << width) - 1)) as u8
+}
+
+/// Writes the `width` low bits of `bits` to the packed entry at the given position (in bits) from
+/// `data`, which must be within the data of a valid tensor.
+unsafe fn write_bits(data: *mut c_void, bit: i128, width: u8, bits: u8) {
+    let (ptr, shift) = ((data as *mut u8).add((bit / 8) as usize), (bit % 8) as u32);
+    let spans_two = shift + width as u32 > 8;
+    let mut word = *ptr as u16;
+    if spans_two {
+        word |= (*ptr.add(1) as u16) << 8;
+    }
+    let mask = ((1_u16 << width) - 1) << shift;
+    word = (word & !mask) | ((bits as u16) << shift);
+    *ptr = word as u8;
+    if spans_two {
+        *ptr.add(1) = (word >> 8) as u8;
+    }
+}
+
+/// The pointer to the first entry of a Tensor along with its shape and strides.
+pub(crate) type Entries<'a, T> = (*mut T, &'a [i64], Cow<'a, [i64]>);
+
+/// Device, data type, shape and strides (in number of elements) of a tensor reinterpreting another
+/// one.
+type Layout = (Device, DataType, Vec<i64>, Vec<i64>);
+
+/// Returns the `ndim` entries `ptr` points to, if `ptr` is non-null or `ndim` is zero.
+unsafe fn dims<'a>(ptr: *const i64, ndim: i32) -> Option<&'a [i64]> {
+    match ndim {
+        0 => Some(&[]),
+        n if n > 0 && !ptr.is_null() => Some(slice::from_raw_parts(ptr, n as usize)),
+        _ => None,
+    }
+}
+
+/// Returns whether the dimensions, given as (size, stride) from the innermost to the outermost,
+/// are laid out one right after another, where size-1 dimensions are ignored.
+fn is_contiguous<'a>(dims: impl Iterator<Item = (&'a i64, &'a i64)>) -> bool {
+    let mut expected = 1_i64;
+    for (&size, &stride) in dims {
+        if size != 1 && stride != expected {
+            return false;
+        }
+        expected = expected.saturating_mul(size);
+    }
+    true
+}
+
+/// Returns whether `f` holds for the offset (in number of elements) of every entry of a non-empty
+/// tensor with the given shape and strides, visiting the entries in row-major order.
+pub(crate) fn all_offsets(shape: &[i64], strides: &[i64], mut f: impl FnMut(i64) -> bool) -> bool {
+    let mut index = vec![0_i64; shape.len()];
+    let mut offset = 0_i64;
+    loop {
+        if !f(offset) {
+            return false;
+        }
+        // steps the innermost dimension, carrying over to the outer ones
+        let mut dim = shape.len();
+        loop {
+            if dim == 0 {
+                return true;
+            }
+            dim -= 1;
+            index[dim] += 1;
+            offset += strides[dim];
+            if index[dim] < shape[dim] {
+                break;
+            }
+            offset -= strides[dim] * shape[dim];
+            index[dim] = 0;
+        }
+    }
 }
 
 /// A typed ManagerContext type that is `!Unpin` i.e. pinnable for safety since it holds a pointer to the underlying DLTensor.
 #[derive(Debug)]
 #[repr(C)]
 pub struct ManagerContext<C> {
-    pub ptr: Option<NonNull<*mut c_void>>,
+    pub ptr: Option<NonNull<c_void>>,
     ty: PhantomData<C>,
     _pin: PhantomPinned,
 }
 
 impl<C> ManagerContext<C> {
-    pub fn new(ptr: Option<NonNull<*mut c_void>>) -> Self {
+    pub fn new(ptr: Option<NonNull<c_void>>) -> Self {
         Self {
             ptr,
             ty: PhantomData,
@@ -166,7 +908,10 @@ impl<C> ManagerContext<C> {
 
 /// Safe proxy to ffi::DLManagedTensor which is self-referential by design.
 /// See [DLManagedTensor](https://dmlc.github.io/dlpack/latest/c_api.html#_CPPv415DLManagedTensor)
-#[pin_project(PinnedDrop)]
+///
+/// The proxy has the same layout as `ffi::DLManagedTensor` so that a `ManagedTensor` can hand it
+/// out to (and take it back from) foreign consumers as is.
+#[pin_project]
 #[repr(C)]
 pub struct ManagedTensorProxy<C> {
     /// Holds the underlying tensor.
@@ -174,11 +919,15 @@ pub struct ManagedTensorProxy<C> {
     /// The context holding the underlying DLTensor.
     #[pin]
     pub manager_ctx: ManagerContext<C>, // safe typed wrapper for *mut c_void which is !Unpin i.e. pinnable
-    /// Deleter function pointer.
-    // TODO: should this be `#[pin]`?
-    pub deleter: Option<fn(&mut ManagedTensor<C>)>,
+    /// Deleter function pointer with the C signature DLPack consumers call it with.
+    pub deleter: Option<unsafe extern "C" fn(*mut DLManagedTensor)>,
 }
 
+const _: () = assert!(
+    mem::size_of::<ManagedTensorProxy<()>>() == mem::size_of::<DLManagedTensor>()
+        && mem::align_of::<ManagedTensorProxy<()>>() == mem::align_of::<DLManagedTensor>()
+);
+
 impl<C: Debug> Debug for ManagedTensorProxy<C> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("ManagedTensorProxy")
@@ -193,12 +942,12 @@ impl<C> ManagedTensorProxy<C> {
         self.dl_tensor
     }
 
-    pub fn manager_ctx(self: Pin<&mut Self>) -> Option<NonNull<*mut c_void>> {
+    pub fn manager_ctx(self: Pin<&mut Self>) -> Option<NonNull<c_void>> {
         let mut this = self.project();
         this.manager_ctx.as_mut().ptr
     }
 
-    pub fn set_manager_ctx(self: Pin<&mut Self>, manager_ctx: NonNull<*mut c_void>) {
+    pub fn set_manager_ctx(self: Pin<&mut Self>, manager_ctx: NonNull<c_void>) {
         let mut this = self.project();
         let new = ManagerContext::new(Some(manager_ctx));
         this.manager_ctx.set(new);
@@ -206,146 +955,1402 @@ impl<C> ManagedTensorProxy<C> {
 }
 
 impl<C> From<DLManagedTensor> for ManagedTensorProxy<C> {
-    fn from(mut dlmt: DLManagedTensor) -> Self {
-        let ptr: Option<NonNull<*mut c_void>> = if dlmt.manager_ctx.is_null() {
-            None
-        } else {
-            unsafe { Some(NonNull::new_unchecked(&mut dlmt.manager_ctx as *mut _)) }
-        };
-        let manager_ctx = ManagerContext::new(ptr);
-        let deleter = dlmt.deleter.take().map(|del| unsafe {
-            transmute::<unsafe extern "C" fn(*mut DLManagedTensor), fn(&mut ManagedTensor<C>)>(del)
-        });
+    fn from(dlmt: DLManagedTensor) -> Self {
         ManagedTensorProxy {
             dl_tensor: dlmt.dl_tensor,
-            manager_ctx,
-            deleter,
+            manager_ctx: ManagerContext::new(NonNull::new(dlmt.manager_ctx)),
+            deleter: dlmt.deleter,
         }
     }
 }
 
 impl<C> From<ManagedTensorProxy<C>> for DLManagedTensor {
     fn from(pmt: ManagedTensorProxy<C>) -> Self {
-        let dl_tensor = pmt.dl_tensor;
-        let manager_ctx = match pmt.manager_ctx.ptr {
-            None => ptr::null_mut(),
-            Some(nnptr) => unsafe { *nnptr.as_ptr() },
-        };
-        let deleter = unsafe {
-            pmt.deleter.map(|del_fn| {
-                transmute::<fn(&mut ManagedTensor<C>), unsafe extern "C" fn(*mut DLManagedTensor)>(
-                    del_fn,
-                )
-            })
-        };
         DLManagedTensor {
-            dl_tensor,
-            manager_ctx,
-            deleter,
+            dl_tensor: pmt.dl_tensor,
+            manager_ctx: pmt.manager_ctx.ptr.map_or(ptr::null_mut(), NonNull::as_ptr),
+            deleter: pmt.deleter,
         }
     }
 }
 
 impl<C> From<Pin<&mut ManagedTensorProxy<C>>> for DLManagedTensor {
     fn from(pmt: Pin<&mut ManagedTensorProxy<C>>) -> Self {
-        let dl_tensor = pmt.dl_tensor;
-        let manager_ctx = match pmt.manager_ctx.ptr {
-            None => ptr::null_mut(),
-            Some(nnptr) => unsafe { *nnptr.as_ptr() },
-        };
-        let deleter = unsafe {
-            pmt.deleter.map(|del_fn| {
-                transmute::<fn(&mut ManagedTensor<C>), unsafe extern "C" fn(*mut DLManagedTensor)>(
-                    del_fn,
-                )
-            })
-        };
         DLManagedTensor {
-            dl_tensor,
-            manager_ctx,
-            deleter,
+            dl_tensor: pmt.dl_tensor,
+            manager_ctx: pmt.manager_ctx.ptr.map_or(ptr::null_mut(), NonNull::as_ptr),
+            deleter: pmt.deleter,
         }
     }
 }
 
-#[allow(clippy::needless_lifetimes)]
-#[pinned_drop]
-impl<C> PinnedDrop for ManagedTensorProxy<C> {
-    fn drop(mut self: Pin<&mut Self>) {
-        let mut dlm: DLManagedTensor = self.as_mut().into();
-        if let Some(fptr) = self.deleter {
-            unsafe {
-                let cfptr = transmute::<fn(&mut ManagedTensor<C>), fn(*mut DLManagedTensor)>(fptr);
-                cfptr(&mut dlm as *mut _);
-            };
+/// Keeps the owner of the data alive next to the shape and strides its DLTensor points into.
+#[repr(C)]
+struct OwnedContext<O> {
+    _owner: O,
+    _shape: Box<[i64]>,
+    _strides: Box<[i64]>,
+}
+
+impl<O> OwnedContext<O> {
+    /// Moves the owner, shape and strides (in number of elements) into a heap context and returns
+    /// it along with the DLTensor pointing into it.
+    fn allocate(
+        owner: O,
+        data: *mut c_void,
+        device: Device,
+        dtype: DataType,
+        shape: Vec<i64>,
+        strides: Vec<i64>,
+        byte_offset: u64,
+    ) -> (DLTensor, *mut c_void) {
+        debug_assert_eq!(shape.len(), strides.len());
+        let mut shape = shape.into_boxed_slice();
+        let mut strides = strides.into_boxed_slice();
+        let tensor = unsafe {
+            Tensor::new(
+                data,
+                device,
+                shape.len() as i32,
+                dtype,
+                shape.as_mut_ptr(),
+                strides.as_mut_ptr(),
+                byte_offset,
+            )
+        };
+        let ctx = Box::new(OwnedContext {
+            _owner: owner,
+            _shape: shape,
+            _strides: strides,
+        });
+        (tensor.into_inner(), Box::into_raw(ctx) as *mut c_void)
+    }
+}
+
+/// Deleter of the DLManagedTensors allocated by this crate without an owned context.
+unsafe extern "C" fn delete_managed_tensor(ptr: *mut DLManagedTensor) {
+    drop(Box::from_raw(ptr));
+}
+
+/// Deleter of the DLManagedTensors allocated by this crate whose manager_ctx is an `OwnedContext<O>`.
+unsafe extern "C" fn delete_owned_context<O>(ptr: *mut DLManagedTensor) {
+    let dlm = Box::from_raw(ptr);
+    drop(Box::from_raw(dlm.manager_ctx as *mut OwnedContext<O>));
+}
+
+/// Deleter of the DLManagedTensorVersioneds allocated by this crate without an owned context.
+unsafe extern "C" fn delete_managed_tensor_versioned(ptr: *mut DLManagedTensorVersioned) {
+    drop(Box::from_raw(ptr));
+}
+
+/// Deleter of the DLManagedTensorVersioneds allocated by this crate whose manager_ctx is an `OwnedContext<O>`.
+unsafe extern "C" fn delete_owned_context_versioned<O>(ptr: *mut DLManagedTensorVersioned) {
+    let dlm = Box::from_raw(ptr);
+    drop(Box::from_raw(dlm.manager_ctx as *mut OwnedContext<O>));
+}
+
+/// Owner of the tensors whose owner can be taken back with [`ManagedTensor::into_owner`], which is
+/// tagged so that such a manager_ctx is told apart from any other one.
+#[repr(C)]
+struct AnyOwner {
+    tag: u64,
+    owner: Box<dyn Any>,
+}
+
+const ANY_OWNER_TAG: u64 = u64::from_le_bytes(*b"dlpackrs");
+
+impl AnyOwner {
+    fn new<O: Any>(owner: O) -> Self {
+        AnyOwner {
+            tag: ANY_OWNER_TAG,
+            owner: Box::new(owner),
         }
     }
 }
 
+/// Deleter of the DLManagedTensors whose manager_ctx is an `OwnedContext<AnyOwner>`. It is not
+/// generic, so that its address tells whether the manager_ctx of a tensor can be read at all.
+unsafe extern "C" fn delete_any_context(ptr: *mut DLManagedTensor) {
+    delete_owned_context::<AnyOwner>(ptr);
+}
+
+/// Deleter of the DLManagedTensorVersioneds whose owner can be taken back with
+/// [`ManagedTensorVersioned::into_owner`].
+unsafe extern "C" fn delete_any_context_versioned(ptr: *mut DLManagedTensorVersioned) {
+    delete_owned_context_versioned::<AnyOwner>(ptr);
+}
+
+/// Returns the owner held by the manager_ctx if the tensor has been created by `from_any_owner`
+/// with an owner of type `O`.
+#[cfg(any(
+    feature = "arrow",
+    feature = "candle",
+    feature = "ndarray",
+    feature = "nalgebra"
+))]
+unsafe fn take_owner<O: Any>(manager_ctx: *mut c_void) -> O {
+    let ctx = Box::from_raw(manager_ctx as *mut OwnedContext<AnyOwner>);
+    match ctx._owner.owner.downcast::<O>() {
+        Ok(owner) => *owner,
+        Err(_) => unreachable!("the type of the owner has been checked"),
+    }
+}
+
+/// Returns whether the manager_ctx is a tagged `OwnedContext<AnyOwner>` holding an owner of type
+/// `O`. It is only read if the deleter is `expected`, i.e. one of the deleters of such contexts,
+/// since the manager_ctx of foreign tensors may be anything.
+#[cfg(any(
+    feature = "arrow",
+    feature = "candle",
+    feature = "ndarray",
+    feature = "nalgebra"
+))]
+unsafe fn holds_owner<O: Any>(
+    deleter: Option<*const ()>,
+    expected: *const (),
+    manager_ctx: *mut c_void,
+) -> bool {
+    if deleter != Some(expected) || manager_ctx.is_null() {
+        return false;
+    }
+    // the owner comes first in the repr(C) context and the tag first in the repr(C) owner
+    if *(manager_ctx as *const u64) != ANY_OWNER_TAG {
+        return false;
+    }
+    (*(manager_ctx as *const OwnedContext<AnyOwner>))
+        ._owner
+        .owner
+        .is::<O>()
+}
+
+/// Runs the deleter closure `F` held by the manager_ctx, catching panics since they must not
+/// unwind into the foreign caller.
+unsafe fn call_deleter<F>(manager_ctx: *mut c_void, dl_tensor: DLTensor)
+where
+    F: FnOnce(&mut Tensor<'_>) + Send + 'static,
+{
+    let deleter = Box::from_raw(manager_ctx as *mut F);
+    let mut tensor = Tensor::from_inner(dl_tensor);
+    if panic::catch_unwind(AssertUnwindSafe(move || deleter(&mut tensor))).is_err() {
+        process::abort();
+    }
+}
+
+/// Trampoline deleter of the DLManagedTensors created with [`ManagedTensor::with_deleter`].
+unsafe extern "C" fn delete_with_closure<F>(ptr: *mut DLManagedTensor)
+where
+    F: FnOnce(&mut Tensor<'_>) + Send + 'static,
+{
+    let dlm = Box::from_raw(ptr);
+    call_deleter::<F>(dlm.manager_ctx, dlm.dl_tensor);
+}
+
+/// Trampoline deleter of the DLManagedTensorVersioneds created with [`ManagedTensorVersioned::with_deleter`].
+unsafe extern "C" fn delete_with_closure_versioned<F>(ptr: *mut DLManagedTensorVersioned)
+where
+    F: FnOnce(&mut Tensor<'_>) + Send + 'static,
+{
+    let dlm = Box::from_raw(ptr);
+    call_deleter::<F>(dlm.manager_ctx, dlm.dl_tensor);
+}
+
+/// Returns the shape as i64 and its compact row-major strides if it holds exactly `len` elements.
+pub(crate) fn compact_layout(
+    shape: &[usize],
+    len: usize,
+) -> Result<(Vec<i64>, Vec<i64>), ShapeMismatchError> {
+    let mismatch = || ShapeMismatchError {
+        shape: shape.to_vec(),
+        len,
+    };
+    let numel = shape
+        .iter()
+        .try_fold(1_usize, |acc, &dim| acc.checked_mul(dim))
+        .ok_or_else(mismatch)?;
+    if numel != len {
+        return Err(mismatch());
+    }
+    let shape = shape
+        .iter()
+        .map(|&dim| i64::try_from(dim))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|_| mismatch())?;
+    let strides = row_major_strides(&shape).map_err(|_| mismatch())?;
+    Ok((shape, strides))
+}
+
+/// Returns the compact row-major strides (in number of elements) of the given shape.
+/// Fails if a stride overflows, e.g. for shape `[1, i64::MAX, 2]`.
+pub(crate) fn row_major_strides(shape: &[i64]) -> Result<Vec<i64>, ValidationError> {
+    let mut strides = vec![1_i64; shape.len()];
+    for i in (0..shape.len().saturating_sub(1)).rev() {
+        strides[i] = strides[i + 1]
+            .checked_mul(shape[i + 1].max(1))
+            .ok_or(ValidationError::ElementCountOverflow)?;
+    }
+    Ok(strides)
+}
+
 /// ManagedTensor type with Rust as the main owner of the underlying data.
 ///
+/// The underlying DLManagedTensor lives on the heap, so its address stays valid when it is handed
+/// out with [`ManagedTensor::into_raw`] and its deleter is called exactly once, either when the
+/// ManagedTensor is dropped or by the consumer it was handed out to.
+///
 ///  See [DLManagedTensor](https://dmlc.github.io/dlpack/latest/c_api.html#_CPPv415DLManagedTensor)
-#[derive(Debug)]
-#[repr(transparent)]
+///
+/// ## Example
+///
+/// ```
+/// use dlpackrs::ManagedTensor;
+/// let mt = ManagedTensor::from_vec(vec![1f32, 2., 3., 4., 5., 6.], &[2, 3]).unwrap();
+/// assert_eq!(mt.tensor().ndim(), 2);
+/// // hand it out to a consumer which eventually calls its deleter
+/// let ptr = mt.into_raw();
+/// unsafe { (*ptr).deleter.unwrap()(ptr) };
+/// ```
 pub struct ManagedTensor<'tensor, C: 'tensor> {
-    pub inner: ManagedTensorProxy<C>,
+    inner: NonNull<ManagedTensorProxy<C>>,
     _marker: PhantomData<fn(&'tensor ()) -> &'tensor ()>, // invariant wrt 'tensor
 }
 
-impl<'tensor, C> From<DLManagedTensor> for ManagedTensor<'tensor, C> {
-    fn from(dlm: DLManagedTensor) -> Self {
-        let proxy: ManagedTensorProxy<C> = dlm.into();
+impl<'tensor, C: Debug> Debug for ManagedTensor<'tensor, C> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ManagedTensor")
+            .field("inner", self.proxy())
+            .finish()
+    }
+}
+
+impl<'tensor, C: 'tensor> ManagedTensor<'tensor, C> {
+    /// Contructor.
+    pub fn new(tensor: Tensor<'tensor>, manager_ctx: Option<NonNull<c_void>>) -> Self {
+        let proxy = ManagedTensorProxy::<C> {
+            dl_tensor: tensor.into_inner(),
+            manager_ctx: ManagerContext::new(manager_ctx),
+            deleter: Some(delete_managed_tensor),
+        };
+        unsafe { Self::from_raw(Box::into_raw(Box::new(proxy.into()))) }
+    }
+
+    /// Creates a ManagedTensor whose deleter calls the given closure exactly once, either when the
+    /// ManagedTensor is dropped or when the consumer it has been handed out to calls the deleter.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use std::sync::{atomic::{AtomicBool, Ordering}, Arc};
+    /// use dlpackrs::{ffi::DLTensor, ManagedTensor, Tensor};
+    ///
+    /// let deleted = Arc::new(AtomicBool::new(false));
+    /// let flag = deleted.clone();
+    /// let tensor = unsafe { Tensor::from_inner(DLTensor::default()) };
+    /// let mt = ManagedTensor::<f32>::with_deleter(tensor, move |_| {
+    ///     flag.store(true, Ordering::SeqCst);
+    /// });
+    /// let ptr = mt.into_raw();
+    /// unsafe { (*ptr).deleter.unwrap()(ptr) };
+    /// assert!(deleted.load(Ordering::SeqCst));
+    /// ```
+    pub fn with_deleter<F>(tensor: Tensor<'tensor>, deleter: F) -> Self
+    where
+        F: FnOnce(&mut Tensor<'_>) + Send + 'static,
+    {
+        let dlm = DLManagedTensor {
+            dl_tensor: tensor.into_inner(),
+            manager_ctx: Box::into_raw(Box::new(deleter)) as *mut c_void,
+            deleter: Some(delete_with_closure::<F>),
+        };
+        unsafe { Self::from_raw(Box::into_raw(Box::new(dlm))) }
+    }
+
+    /// Creates a ManagedTensor whose manager_ctx keeps `owner` alive along with the shape and
+    /// strides (in number of elements) of the tensor, until the deleter drops them all.
+    pub(crate) fn from_owner<O>(
+        owner: O,
+        data: *mut c_void,
+        device: Device,
+        dtype: DataType,
+        shape: Vec<i64>,
+        strides: Vec<i64>,
+        byte_offset: u64,
+    ) -> Self {
+        let (dl_tensor, manager_ctx) =
+            OwnedContext::allocate(owner, data, device, dtype, shape, strides, byte_offset);
+        let dlm = DLManagedTensor {
+            dl_tensor,
+            manager_ctx,
+            deleter: Some(delete_owned_context::<O>),
+        };
+        unsafe { Self::from_raw(Box::into_raw(Box::new(dlm))) }
+    }
+
+    /// Same as [`ManagedTensor::from_owner`], except that the owner can be taken back with
+    /// [`ManagedTensor::into_owner`].
+    pub(crate) fn from_any_owner<O: Any>(
+        owner: O,
+        data: *mut c_void,
+        device: Device,
+        dtype: DataType,
+        shape: Vec<i64>,
+        strides: Vec<i64>,
+        byte_offset: u64,
+    ) -> Self {
+        let owner = AnyOwner::new(owner);
+        let mut mt = Self::from_owner(owner, data, device, dtype, shape, strides, byte_offset);
+        unsafe { mt.inner.as_mut().deleter = Some(delete_any_context) };
+        mt
+    }
+
+    /// Takes back the owner of a ManagedTensor created by [`ManagedTensor::from_any_owner`] without
+    /// calling the deleter, or returns the ManagedTensor as is if it holds anything else.
+    #[cfg(any(feature = "arrow", feature = "ndarray", feature = "nalgebra"))]
+    pub(crate) fn into_owner<O: Any>(self) -> Result<O, Self> {
+        let proxy = self.proxy();
+        let deleter = proxy.deleter.map(|deleter| deleter as *const ());
+        let expected = delete_any_context as *const ();
+        if !unsafe {
+            holds_owner::<O>(
+                deleter,
+                expected,
+                proxy
+                    .manager_ctx
+                    .ptr
+                    .map_or(ptr::null_mut(), NonNull::as_ptr),
+            )
+        } {
+            return Err(self);
+        }
+        let dlm = unsafe { Box::from_raw(self.into_raw()) };
+        Ok(unsafe { take_owner(dlm.manager_ctx) })
+    }
+
+    /// Returns the underlying proxy.
+    pub fn proxy(&self) -> &ManagedTensorProxy<C> {
+        unsafe { self.inner.as_ref() }
+    }
+
+    /// Returns the pinned underlying proxy.
+    ///
+    /// # Safety
+    ///
+    /// The DLTensor of the proxy must keep satisfying the contract of [`Tensor::new`] for
+    /// `'tensor`, and its deleter must keep releasing the manager context.
+    pub unsafe fn proxy_mut(&mut self) -> Pin<&mut ManagedTensorProxy<C>> {
+        Pin::new_unchecked(self.inner.as_mut())
+    }
+
+    /// Returns the underlying Tensor.
+    pub fn tensor(&self) -> &Tensor<'tensor> {
+        unsafe { &*(&self.proxy().dl_tensor as *const DLTensor as *const Tensor<'tensor>) }
+    }
+
+    /// Returns the underlying Tensor mutably.
+    pub fn tensor_mut(&mut self) -> TensorMut<'_, 'tensor> {
+        TensorMut(unsafe {
+            &mut *(&mut self.inner.as_mut().dl_tensor as *mut DLTensor as *mut Tensor<'tensor>)
+        })
+    }
+
+    /// Returns the manager context pointer.
+    pub fn manager_ctx(&self) -> Option<NonNull<c_void>> {
+        self.proxy().manager_ctx.ptr
+    }
+
+    /// Consumes the ManagedTensor and returns the raw pointer to its underlying DLManagedTensor.
+    /// The receiver takes over the responsibility of calling its deleter.
+    pub fn into_raw(self) -> *mut DLManagedTensor {
+        let ptr = self.inner.as_ptr() as *mut DLManagedTensor;
+        mem::forget(self);
+        ptr
+    }
+
+    /// Returns a ManagedTensor instances from a raw pointer to DLManagedTensor (must be non-null).
+    /// The ManagedTensor takes over the ownership and calls the deleter, if any, once dropped.
+    pub unsafe fn from_raw(ptr: *mut DLManagedTensor) -> Self {
+        debug_assert!(!ptr.is_null());
         ManagedTensor {
-            inner: proxy,
+            inner: NonNull::new_unchecked(ptr as *mut ManagedTensorProxy<C>),
             _marker: PhantomData,
         }
     }
+
+    /// Consumes the ManagedTensor and returns Tensor without calling the deleter,
+    /// i.e. the memory backing the tensor is leaked.
+    pub fn into_tensor(self) -> Tensor<'tensor> {
+        let dl_tensor = self.proxy().dl_tensor;
+        mem::forget(self);
+        // the shape, strides and data are leaked, hence valid for 'tensor
+        unsafe { Tensor::from_inner(dl_tensor) }
+    }
+
+    /// Reinterprets a complex tensor as a real one with a trailing dimension of size 2 holding the
+    /// real and imaginary parts, without copying. The returned ManagedTensor keeps this one alive.
+    ///
+    /// Fails unless the data type is complex, returning the tensor along with the error.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use dlpackrs::{DataType, ManagedTensor};
+    /// let mt = ManagedTensor::<f32>::from_vec(vec![1., 2., 3., 4.], &[2, 2]).unwrap();
+    /// let mt = mt.view_as_complex().unwrap();
+    /// assert_eq!(mt.tensor().dtype(), DataType::complex(64, 1));
+    /// assert_eq!(mt.tensor().shape().unwrap(), vec![2]);
+    /// let mt = mt.view_as_real().unwrap();
+    /// assert_eq!(mt.tensor().as_slice::<f32>().unwrap(), &[1., 2., 3., 4.]);
+    /// ```
+    pub fn view_as_real(self) -> Result<Self, (Self, TensorAccessError)> {
+        match self.tensor().real_layout() {
+            Ok(layout) => Ok(self.reinterpret(layout)),
+            Err(e) => Err((self, e)),
+        }
+    }
+
+    /// Reinterprets a real tensor whose trailing dimension of size 2 is contiguous, and whose other
+    /// strides are even, as a complex one without copying. The returned ManagedTensor keeps this
+    /// one alive.
+    ///
+    /// Fails otherwise, returning the tensor along with the error.
+    pub fn view_as_complex(self) -> Result<Self, (Self, TensorAccessError)> {
+        match self.tensor().complex_layout() {
+            Ok(layout) => Ok(self.reinterpret(layout)),
+            Err(e) => Err((self, e)),
+        }
+    }
+
+    /// Returns a ManagedTensor with the given layout over the data of this one, which it keeps alive.
+    fn reinterpret(self, (device, dtype, shape, strides): Layout) -> Self {
+        let t = self.tensor();
+        let (data, byte_offset) = (t.data(), t.inner.byte_offset);
+        Self::from_owner(self, data, device, dtype, shape, strides, byte_offset)
+    }
+}
+
+impl<'tensor, T: Element> ManagedTensor<'tensor, T> {
+    /// Creates a compact row-major ManagedTensor on cpu that owns the given data.
+    pub fn from_vec(data: Vec<T>, shape: &[usize]) -> Result<Self, ShapeMismatchError> {
+        let (shape, strides) = compact_layout(shape, data.len())?;
+        let mut data = data;
+        let ptr = data.as_mut_ptr() as *mut c_void;
+        Ok(Self::from_any_owner(
+            data,
+            ptr,
+            Device::default(),
+            T::DTYPE,
+            shape,
+            strides,
+            0,
+        ))
+    }
+
+    /// Creates a compact row-major ManagedTensor on cpu that owns the given data.
+    pub fn from_boxed_slice(data: Box<[T]>, shape: &[usize]) -> Result<Self, ShapeMismatchError> {
+        Self::from_vec(data.into_vec(), shape)
+    }
+}
+
+impl<'tensor, C: 'tensor> Drop for ManagedTensor<'tensor, C> {
+    fn drop(&mut self) {
+        let ptr = self.inner.as_ptr() as *mut DLManagedTensor;
+        unsafe {
+            if let Some(deleter) = (*ptr).deleter {
+                deleter(ptr);
+            }
+        }
+    }
+}
+
+/// Bit flags of a [`ManagedTensorVersioned`].
+/// See [DLPACK_FLAG_BITMASK_READ_ONLY](https://dmlc.github.io/dlpack/latest/c_api.html#c.DLPACK_FLAG_BITMASK_READ_ONLY)
+///
+/// ## Example
+///
+/// ```
+/// use dlpackrs::TensorFlags;
+/// let flags = TensorFlags::READ_ONLY | TensorFlags::IS_COPIED;
+/// assert!(flags.contains(TensorFlags::READ_ONLY));
+/// assert_eq!(flags.bits(), 0b11);
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct TensorFlags(u64);
+
+impl TensorFlags {
+    /// The tensor is read-only, i.e. the consumer must not write to its data.
+    pub const READ_ONLY: TensorFlags = TensorFlags(ffi::DLPACK_FLAG_BITMASK_READ_ONLY as u64);
+    /// The data of the tensor has been copied by the producer from the original source.
+    pub const IS_COPIED: TensorFlags = TensorFlags(ffi::DLPACK_FLAG_BITMASK_IS_COPIED as u64);
+    /// The entries of a sub-byte data type, e.g. int4, are padded to a byte each instead of being
+    /// packed.
+    pub const IS_SUBBYTE_TYPE_PADDED: TensorFlags =
+        TensorFlags(ffi::DLPACK_FLAG_BITMASK_IS_SUBBYTE_TYPE_PADDED as u64);
+
+    pub const fn empty() -> TensorFlags {
+        TensorFlags(0)
+    }
+
+    /// Creates the flags from their raw bits, keeping the bits unknown to this crate.
+    pub const fn from_bits(bits: u64) -> TensorFlags {
+        TensorFlags(bits)
+    }
+
+    pub const fn bits(&self) -> u64 {
+        self.0
+    }
+
+    pub const fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+
+    /// Returns whether all the given flags are set.
+    pub const fn contains(&self, other: TensorFlags) -> bool {
+        self.0 & other.0 == other.0
+    }
 }
 
-impl<'tensor, C> From<ManagedTensor<'tensor, C>> for DLManagedTensor {
-    fn from(mt: ManagedTensor<'tensor, C>) -> Self {
-        mt.inner.into()
+impl BitOr for TensorFlags {
+    type Output = TensorFlags;
+
+    fn bitor(self, rhs: TensorFlags) -> TensorFlags {
+        TensorFlags(self.0 | rhs.0)
     }
 }
 
-impl<'tensor, C: 'tensor> ManagedTensor<'tensor, C> {
+impl BitOrAssign for TensorFlags {
+    fn bitor_assign(&mut self, rhs: TensorFlags) {
+        self.0 |= rhs.0;
+    }
+}
+
+/// ManagedTensor type for DLPack 1.x which carries the DLPack version it has been produced with
+/// and bit flags describing the underlying data.
+///
+/// See [DLManagedTensorVersioned](https://dmlc.github.io/dlpack/latest/c_api.html#_CPPv424DLManagedTensorVersioned)
+///
+/// ## Example
+///
+/// ```
+/// use dlpackrs::ManagedTensorVersioned;
+/// let mt = ManagedTensorVersioned::from_vec(vec![1f64, 2., 3.], &[3]).unwrap();
+/// assert_eq!(mt.version(), dlpackrs::dlpack_version());
+/// let ptr = mt.into_raw();
+/// // a consumer takes the tensor back and drops it eventually
+/// let mt = unsafe { ManagedTensorVersioned::<f64>::from_raw(ptr) }.unwrap();
+/// assert_eq!(mt.tensor().as_slice::<f64>().unwrap(), &[1., 2., 3.]);
+/// ```
+pub struct ManagedTensorVersioned<'tensor, C: 'tensor> {
+    inner: NonNull<DLManagedTensorVersioned>,
+    ty: PhantomData<C>,
+    _marker: PhantomData<fn(&'tensor ()) -> &'tensor ()>, // invariant wrt 'tensor
+}
+
+impl<'tensor, C> Debug for ManagedTensorVersioned<'tensor, C> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let dlm = unsafe { self.inner.as_ref() };
+        f.debug_struct("ManagedTensorVersioned")
+            .field("version", &dlm.version)
+            .field("flags", &dlm.flags)
+            .field("dl_tensor", &dlm.dl_tensor)
+            .field("manager_ctx", &dlm.manager_ctx)
+            .finish()
+    }
+}
+
+impl<'tensor, C: 'tensor> ManagedTensorVersioned<'tensor, C> {
     /// Contructor.
-    pub fn new(tensor: Tensor<'tensor>, manager_ctx: Option<NonNull<*mut c_void>>) -> Self {
-        let manager_ctx = ManagerContext::new(manager_ctx);
-        let inner = ManagedTensorProxy {
+    pub fn new(tensor: Tensor<'tensor>, manager_ctx: Option<NonNull<c_void>>) -> Self {
+        let dlm = DLManagedTensorVersioned {
+            version: crate::dlpack_version(),
+            manager_ctx: manager_ctx.map_or(ptr::null_mut(), NonNull::as_ptr),
+            deleter: Some(delete_managed_tensor_versioned),
+            flags: 0,
             dl_tensor: tensor.into_inner(),
+        };
+        Self::from_box(Box::new(dlm))
+    }
+
+    /// Same as [`ManagedTensor::with_deleter`] for the versioned layout.
+    pub fn with_deleter<F>(tensor: Tensor<'tensor>, deleter: F) -> Self
+    where
+        F: FnOnce(&mut Tensor<'_>) + Send + 'static,
+    {
+        let dlm = DLManagedTensorVersioned {
+            version: crate::dlpack_version(),
+            manager_ctx: Box::into_raw(Box::new(deleter)) as *mut c_void,
+            deleter: Some(delete_with_closure_versioned::<F>),
+            flags: 0,
+            dl_tensor: tensor.into_inner(),
+        };
+        Self::from_box(Box::new(dlm))
+    }
+
+    /// Same as [`ManagedTensor::from_owner`] for the versioned layout.
+    pub(crate) fn from_owner<O>(
+        owner: O,
+        data: *mut c_void,
+        device: Device,
+        dtype: DataType,
+        shape: Vec<i64>,
+        strides: Vec<i64>,
+        byte_offset: u64,
+    ) -> Self {
+        let (dl_tensor, manager_ctx) =
+            OwnedContext::allocate(owner, data, device, dtype, shape, strides, byte_offset);
+        let dlm = DLManagedTensorVersioned {
+            version: crate::dlpack_version(),
             manager_ctx,
-            deleter: None,
+            deleter: Some(delete_owned_context_versioned::<O>),
+            flags: 0,
+            dl_tensor,
         };
+        Self::from_box(Box::new(dlm))
+    }
 
-        ManagedTensor {
-            inner,
+    /// Same as [`ManagedTensor::from_any_owner`] for the versioned layout.
+    pub(crate) fn from_any_owner<O: Any>(
+        owner: O,
+        data: *mut c_void,
+        device: Device,
+        dtype: DataType,
+        shape: Vec<i64>,
+        strides: Vec<i64>,
+        byte_offset: u64,
+    ) -> Self {
+        let owner = AnyOwner::new(owner);
+        let mut mt = Self::from_owner(owner, data, device, dtype, shape, strides, byte_offset);
+        unsafe { mt.inner.as_mut().deleter = Some(delete_any_context_versioned) };
+        mt
+    }
+
+    /// Same as [`ManagedTensor::into_owner`] for the versioned layout.
+    #[cfg(any(
+        feature = "arrow",
+        feature = "candle",
+        feature = "ndarray",
+        feature = "nalgebra"
+    ))]
+    pub(crate) fn into_owner<O: Any>(self) -> Result<O, Self> {
+        let dlm = unsafe { self.inner.as_ref() };
+        let deleter = dlm.deleter.map(|deleter| deleter as *const ());
+        let expected = delete_any_context_versioned as *const ();
+        if !unsafe { holds_owner::<O>(deleter, expected, dlm.manager_ctx) } {
+            return Err(self);
+        }
+        let dlm = unsafe { Box::from_raw(self.into_raw()) };
+        Ok(unsafe { take_owner(dlm.manager_ctx) })
+    }
+
+    fn from_box(dlm: Box<DLManagedTensorVersioned>) -> Self {
+        ManagedTensorVersioned {
+            inner: unsafe { NonNull::new_unchecked(Box::into_raw(dlm)) },
+            ty: PhantomData,
             _marker: PhantomData,
         }
     }
 
-    /// Sets a deleter function pointer.
-    pub fn set_deleter(&mut self, deleter: fn(&mut ManagedTensor<C>)) {
-        self.inner.deleter = Some(deleter);
+    /// Returns the DLPack version the tensor has been produced with.
+    pub fn version(&self) -> DLPackVersion {
+        unsafe { self.inner.as_ref().version }
     }
 
-    /// Consumes the ManagedTensor and returns the raw pointer to its underlying DLManagedTensor.
-    pub fn into_raw(self) -> *const DLManagedTensor {
-        let ret: DLManagedTensor = self.inner.into();
-        &ret as *const _
+    /// Returns the bit flags of the tensor.
+    pub fn flags(&self) -> TensorFlags {
+        TensorFlags::from_bits(unsafe { self.inner.as_ref().flags })
     }
 
-    /// Returns a ManagedTensor instances from a raw pointer to DLManagedTensor.
-    pub unsafe fn from_raw(ptr: *mut DLManagedTensor) -> Self {
+    /// Sets the given flags in addition to the ones already set, e.g. before handing the tensor out.
+    /// Flags can not be cleared, so that a read-only tensor stays read-only.
+    pub fn with_flags(mut self, flags: TensorFlags) -> Self {
+        unsafe { self.inner.as_mut().flags |= flags.bits() };
+        self
+    }
+
+    /// Returns whether the tensor must not be written to.
+    pub fn is_read_only(&self) -> bool {
+        self.flags().contains(TensorFlags::READ_ONLY)
+    }
+
+    /// Returns whether the data of the tensor has been copied by its producer.
+    pub fn is_copied(&self) -> bool {
+        self.flags().contains(TensorFlags::IS_COPIED)
+    }
+
+    /// Returns the underlying Tensor.
+    pub fn tensor(&self) -> &Tensor<'tensor> {
+        unsafe { &*(&self.inner.as_ref().dl_tensor as *const DLTensor as *const Tensor<'tensor>) }
+    }
+
+    /// Same as [`ManagedTensor::view_as_real`], keeping the flags of the tensor.
+    pub fn view_as_real(self) -> Result<Self, (Self, TensorAccessError)> {
+        match self.tensor().real_layout() {
+            Ok(layout) => Ok(self.reinterpret(layout)),
+            Err(e) => Err((self, e)),
+        }
+    }
+
+    /// Same as [`ManagedTensor::view_as_complex`], keeping the flags of the tensor.
+    pub fn view_as_complex(self) -> Result<Self, (Self, TensorAccessError)> {
+        match self.tensor().complex_layout() {
+            Ok(layout) => Ok(self.reinterpret(layout)),
+            Err(e) => Err((self, e)),
+        }
+    }
+
+    /// Returns a ManagedTensorVersioned with the given layout and the same flags over the data of
+    /// this one, which it keeps alive.
+    fn reinterpret(self, (device, dtype, shape, strides): Layout) -> Self {
+        let t = self.tensor();
+        let (data, byte_offset) = (t.data(), t.inner.byte_offset);
+        let flags = self.flags();
+        Self::from_owner(self, data, device, dtype, shape, strides, byte_offset).with_flags(flags)
+    }
+
+    /// Returns the underlying Tensor mutably, unless the tensor is read-only.
+    pub fn tensor_mut(&mut self) -> Result<TensorMut<'_, 'tensor>, TensorAccessError> {
+        if self.is_read_only() {
+            return Err(TensorAccessError::ReadOnly);
+        }
+        Ok(TensorMut(unsafe {
+            &mut *(&mut self.inner.as_mut().dl_tensor as *mut DLTensor as *mut Tensor<'tensor>)
+        }))
+    }
+
+    /// Same as [`Tensor::get_packed`], where the entries of less than a byte take a byte each if
+    /// the tensor has the [`TensorFlags::IS_SUBBYTE_TYPE_PADDED`] flag.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use std::{os::raw::c_void, ptr};
+    /// use dlpackrs::{DataType, Device, ManagedTensorVersioned, Tensor, TensorFlags};
+    ///
+    /// // int4 entries 1 and -2, each in its own byte
+    /// let mut data = [0x01_u8, 0x0e];
+    /// let mut shape = [2_i64];
+    /// let t = unsafe { Tensor::new(
+    ///     data.as_mut_ptr() as *mut c_void,
+    ///     Device::default(),
+    ///     1,
+    ///     DataType::int(4, 1),
+    ///     shape.as_mut_ptr(),
+    ///     ptr::null_mut(),
+    ///     0,
+    /// ) };
+    /// let mut mt = ManagedTensorVersioned::<i8>::with_deleter(t, |_| {})
+    ///     .with_flags(TensorFlags::IS_SUBBYTE_TYPE_PADDED);
+    /// assert_eq!(mt.unpack::<i8>().unwrap(), vec![1, -2]);
+    /// mt.set_packed(&[1], 7_i8).unwrap();
+    /// drop(mt);
+    /// assert_eq!(data, [0x01, 0x07]);
+    /// ```
+    pub fn get_packed<T: PackedElement>(&self, index: &[usize]) -> Result<T, TensorAccessError> {
+        self.tensor().get_packed_with(index, self.is_padded())
+    }
+
+    /// Same as [`Tensor::set_packed`], honoring the padding of the entries as
+    /// [`ManagedTensorVersioned::get_packed`] does, and also fails if the tensor is read-only.
+    pub fn set_packed<T: PackedElement>(
+        &mut self,
+        index: &[usize],
+        value: T,
+    ) -> Result<(), TensorAccessError> {
+        let padded = self.is_padded();
+        self.tensor_mut()?.0.set_packed_with(index, value, padded)
+    }
+
+    /// Same as [`Tensor::iter_packed`], honoring the padding of the entries as
+    /// [`ManagedTensorVersioned::get_packed`] does.
+    pub fn iter_packed<T: PackedElement>(
+        &self,
+    ) -> Result<impl Iterator<Item = T> + '_, TensorAccessError> {
+        self.tensor().iter_packed_with(self.is_padded())
+    }
+
+    /// Same as [`Tensor::unpack`], honoring the padding of the entries as
+    /// [`ManagedTensorVersioned::get_packed`] does.
+    pub fn unpack<T: PackedElement>(&self) -> Result<Vec<T>, TensorAccessError> {
+        Ok(self.iter_packed()?.collect())
+    }
+
+    fn is_padded(&self) -> bool {
+        self.flags().contains(TensorFlags::IS_SUBBYTE_TYPE_PADDED)
+    }
+
+    /// Returns the manager context pointer.
+    pub fn manager_ctx(&self) -> Option<NonNull<c_void>> {
+        NonNull::new(unsafe { self.inner.as_ref().manager_ctx })
+    }
+
+    /// Consumes the ManagedTensorVersioned and returns the raw pointer to its underlying
+    /// DLManagedTensorVersioned. The receiver takes over the responsibility of calling its deleter.
+    pub fn into_raw(self) -> *mut DLManagedTensorVersioned {
+        let ptr = self.inner.as_ptr();
+        mem::forget(self);
+        ptr
+    }
+
+    /// Returns a ManagedTensorVersioned instance from a raw pointer to DLManagedTensorVersioned
+    /// (must be non-null), taking over its ownership.
+    ///
+    /// Fails if the tensor has been produced with an incompatible major version of DLPack, in which
+    /// case the tensor is released right away by calling its deleter.
+    pub unsafe fn from_raw(
+        ptr: *mut DLManagedTensorVersioned,
+    ) -> Result<Self, UnsupportedVersionError> {
         debug_assert!(!ptr.is_null());
-        ManagedTensor {
-            inner: (*ptr).into(),
+        let version = (*ptr).version;
+        if version.major != ffi::DLPACK_MAJOR_VERSION {
+            if let Some(deleter) = (*ptr).deleter {
+                deleter(ptr);
+            }
+            return Err(UnsupportedVersionError(version.major, version.minor));
+        }
+        Ok(ManagedTensorVersioned {
+            inner: NonNull::new_unchecked(ptr),
+            ty: PhantomData,
             _marker: PhantomData,
+        })
+    }
+}
+
+impl<'tensor, T: Element> ManagedTensorVersioned<'tensor, T> {
+    /// Creates a compact row-major ManagedTensorVersioned on cpu that owns the given data.
+    pub fn from_vec(data: Vec<T>, shape: &[usize]) -> Result<Self, ShapeMismatchError> {
+        let (shape, strides) = compact_layout(shape, data.len())?;
+        let mut data = data;
+        let ptr = data.as_mut_ptr() as *mut c_void;
+        Ok(Self::from_any_owner(
+            data,
+            ptr,
+            Device::default(),
+            T::DTYPE,
+            shape,
+            strides,
+            0,
+        ))
+    }
+
+    /// Creates a compact row-major ManagedTensorVersioned on cpu that owns the given data.
+    pub fn from_boxed_slice(data: Box<[T]>, shape: &[usize]) -> Result<Self, ShapeMismatchError> {
+        Self::from_vec(data.into_vec(), shape)
+    }
+}
+
+impl<'tensor, C: 'tensor> Drop for ManagedTensorVersioned<'tensor, C> {
+    fn drop(&mut self) {
+        let ptr = self.inner.as_ptr();
+        unsafe {
+            if let Some(deleter) = (*ptr).deleter {
+                deleter(ptr);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn owned_managed_tensor() {
+        let mt = ManagedTensor::from_vec((0..6).collect::<Vec<i32>>(), &[2, 3]).unwrap();
+        let t = mt.tensor();
+        assert_eq!(t.ndim(), 2);
+        assert_eq!(t.dtype(), DataType::i32());
+        assert_eq!(t.shape().unwrap(), vec![2, 3]);
+        assert_eq!(t.strides(), Some(vec![3, 1]));
+        assert_eq!(t.as_slice::<i32>().unwrap(), &[0, 1, 2, 3, 4, 5]);
+        drop(mt);
+
+        let mt = ManagedTensor::from_boxed_slice(vec![1u8; 4].into_boxed_slice(), &[4]).unwrap();
+        let ptr = mt.into_raw();
+        unsafe { (*ptr).deleter.unwrap()(ptr) };
+
+        assert!(ManagedTensor::from_vec(vec![0f32; 5], &[2, 3]).is_err());
+    }
+
+    #[cfg(feature = "half")]
+    #[test]
+    fn half_precision() {
+        use half::{bf16, f16};
+
+        let values = [1.5, -2., 0.25];
+        let mt = ManagedTensor::from_vec(values.map(f16::from_f32).to_vec(), &[3]).unwrap();
+        let t = mt.tensor();
+        assert_eq!(t.dtype(), DataType::f16());
+        assert_eq!(t.itemsize(), 2);
+        let entries = t.as_slice::<f16>().unwrap();
+        assert_eq!(
+            entries.iter().map(|x| x.to_f32()).collect::<Vec<_>>(),
+            values
+        );
+        assert_eq!(entries[0].to_string(), "1.5");
+        assert!(t.as_slice::<bf16>().is_err());
+
+        let mt = ManagedTensor::from_vec(vec![bf16::from_f64(3.); 4], &[2, 2]).unwrap();
+        assert_eq!(mt.tensor().dtype(), DataType::bf16());
+        assert_eq!(
+            mt.tensor().as_slice::<bf16>().unwrap()[3],
+            bf16::from_f32(3.)
+        );
+    }
+
+    #[test]
+    fn complex_views() {
+        let mt =
+            ManagedTensorVersioned::<f64>::from_vec((0..12).map(f64::from).collect(), &[3, 2, 2])
+                .unwrap()
+                .with_flags(TensorFlags::READ_ONLY);
+        let mt = mt.view_as_complex().unwrap();
+        let t = mt.tensor();
+        assert!(mt.is_read_only());
+        assert_eq!(t.dtype(), DataType::complex(128, 1));
+        assert_eq!(t.shape().unwrap(), vec![3, 2]);
+        assert_eq!(t.strides(), Some(vec![2, 1]));
+        #[cfg(feature = "num-complex")]
+        assert_eq!(
+            t.as_slice::<num_complex::Complex<f64>>().unwrap()[3],
+            num_complex::Complex::new(6., 7.)
+        );
+        let mt = mt.view_as_real().unwrap();
+        assert_eq!(mt.tensor().shape().unwrap(), vec![3, 2, 2]);
+        assert_eq!(mt.tensor().as_slice::<f64>().unwrap()[11], 11.);
+        let mt = match mt.view_as_real() {
+            Err((mt, TensorAccessError::IncompatibleDataType(_))) => mt,
+            _ => panic!("real tensors have no real view"),
+        };
+        assert_eq!(mt.tensor().as_slice::<f64>().unwrap()[11], 11.);
+
+        // the trailing dimension must hold contiguous pairs
+        let mut data = [0f32; 6];
+        let mut shape = [2_i64, 2];
+        let mut strides = [1_i64, 2];
+        let t = unsafe {
+            Tensor::new(
+                data.as_mut_ptr() as *mut c_void,
+                Device::default(),
+                2,
+                DataType::f32(),
+                shape.as_mut_ptr(),
+                strides.as_mut_ptr(),
+                0,
+            )
+        };
+        let mt = ManagedTensor::<()>::new(t, None);
+        assert!(matches!(
+            mt.view_as_complex(),
+            Err((_, TensorAccessError::IncompatibleStrides(_)))
+        ));
+    }
+
+    #[test]
+    fn packed_entries() {
+        // uint4 entries 0..6 of a 2x3 tensor, read through its transpose
+        let mut data = [0x10_u8, 0x32, 0x54];
+        let mut shape = [3_i64, 2];
+        let mut strides = [1_i64, 3];
+        let mut t = unsafe {
+            Tensor::new(
+                data.as_mut_ptr() as *mut c_void,
+                Device::default(),
+                2,
+                DataType::uint(4, 1),
+                shape.as_mut_ptr(),
+                strides.as_mut_ptr(),
+                0,
+            )
+        };
+        assert!(t.is_packed());
+        assert_eq!((t.item_bits(), t.itemsize(), t.size()), (4, 1, Some(3)));
+        assert_eq!(t.unpack::<u8>().unwrap(), vec![0, 3, 1, 4, 2, 5]);
+        assert!(t.unpack::<i8>().is_err());
+        assert!(t.as_slice::<u8>().is_err());
+        t.set_packed(&[2, 0], 15_u8).unwrap();
+        assert_eq!(data[1], 0x3f);
+        assert!(matches!(
+            t.set_packed(&[0, 0], 16_u8),
+            Err(TensorAccessError::ValueOutOfRange(4))
+        ));
+        assert!(matches!(
+            t.get_packed::<u8>(&[3, 0]),
+            Err(TensorAccessError::IndexOutOfBounds(_))
+        ));
+
+        // 1-bit booleans starting from the second byte
+        let mut data = [0xff_u8, 0b1010_0101];
+        let mut shape = [7_i64];
+        let mut t = unsafe {
+            Tensor::new(
+                data.as_mut_ptr() as *mut c_void,
+                Device::default(),
+                1,
+                DataType::new(DataTypeCode::Bool as u8, 1, 1),
+                shape.as_mut_ptr(),
+                ptr::null_mut(),
+                1,
+            )
+        };
+        assert_eq!(t.size(), Some(1));
+        let bits = t.iter_packed::<bool>().unwrap().collect::<Vec<_>>();
+        assert_eq!(bits, [true, false, true, false, false, true, false]);
+        t.set_packed(&[6], true).unwrap();
+        assert_eq!(data, [0xff, 0b1110_0101]);
+
+        // int3 entries spanning two bytes
+        let mut data = [0_u8; 2];
+        let mut shape = [5_i64];
+        let mut t = unsafe {
+            Tensor::new(
+                data.as_mut_ptr() as *mut c_void,
+                Device::default(),
+                1,
+                DataType::int(3, 1),
+                shape.as_mut_ptr(),
+                ptr::null_mut(),
+                0,
+            )
+        };
+        assert_eq!(t.size(), Some(2));
+        for (i, value) in [-4_i8, 3, -1, 2, 0].iter().enumerate() {
+            t.set_packed(&[i], *value).unwrap();
         }
+        assert_eq!(t.unpack::<i8>().unwrap(), vec![-4, 3, -1, 2, 0]);
+        assert_eq!(data, [0b1101_1100, 0b0000_0101]);
+
+        // uint2 entries of a 2x2 tensor padded to a byte each, read through its transpose
+        let mut data = [0_u8, 1, 2, 3];
+        let mut shape = [2_i64, 2];
+        let mut strides = [1_i64, 2];
+        let t = unsafe {
+            Tensor::new(
+                data.as_mut_ptr() as *mut c_void,
+                Device::default(),
+                2,
+                DataType::uint(2, 1),
+                shape.as_mut_ptr(),
+                strides.as_mut_ptr(),
+                0,
+            )
+        };
+        let mut mt = ManagedTensorVersioned::<u8>::with_deleter(t, |_| {})
+            .with_flags(TensorFlags::IS_SUBBYTE_TYPE_PADDED);
+        assert_eq!(mt.unpack::<u8>().unwrap(), vec![0, 2, 1, 3]);
+        mt.set_packed(&[1, 0], 3_u8).unwrap();
+        assert_eq!(mt.get_packed::<u8>(&[1, 0]).unwrap(), 3);
+        let mut mt = mt.with_flags(TensorFlags::READ_ONLY);
+        assert!(matches!(
+            mt.set_packed(&[0, 0], 1_u8),
+            Err(TensorAccessError::ReadOnly)
+        ));
+        drop(mt);
+        assert_eq!(data, [0, 3, 2, 3]);
+
+        // float4 entries 6, -0.5
+        let mut data = [0x97_u8];
+        let mut shape = [2_i64];
+        let mut t = unsafe {
+            Tensor::new(
+                data.as_mut_ptr() as *mut c_void,
+                Device::default(),
+                1,
+                DataType::float4_e2m1fn(),
+                shape.as_mut_ptr(),
+                ptr::null_mut(),
+                0,
+            )
+        };
+        assert_eq!(t.unpack::<f32>().unwrap(), vec![6., -0.5]);
+        t.set_packed(&[1], 1.5_f32).unwrap();
+        assert_eq!(data, [0x37]);
+
+        // float8 entries 448, -1
+        let mut data = [0x7e_u8, 0xb8];
+        let mut t = unsafe {
+            Tensor::new(
+                data.as_mut_ptr() as *mut c_void,
+                Device::default(),
+                1,
+                DataType::float8_e4m3fn(),
+                shape.as_mut_ptr(),
+                ptr::null_mut(),
+                0,
+            )
+        };
+        assert!(!t.is_packed());
+        assert_eq!(t.unpack::<f32>().unwrap(), vec![448., -1.]);
+        t.set_packed(&[1], 0.5_f32).unwrap();
+        assert_eq!(data, [0x7e, 0x30]);
     }
 
-    /// Consumes the ManagedTensor and returns Tensor.
-    pub fn into_tensor(self) -> Tensor<'tensor> {
-        self.inner.dl_tensor.into()
+    #[test]
+    fn closure_deleter() {
+        use std::sync::{
+            atomic::{AtomicUsize, Ordering},
+            Arc,
+        };
+
+        let calls = Arc::new(AtomicUsize::new(0));
+        let mut data = vec![1f32, 2., 3.];
+        let mut shape = [3_i64];
+        let mut tensor = || unsafe {
+            Tensor::new(
+                data.as_mut_ptr() as *mut c_void,
+                Device::default(),
+                1,
+                DataType::f32(),
+                shape.as_mut_ptr(),
+                ptr::null_mut(),
+                0,
+            )
+        };
+        let counter = calls.clone();
+        let mt = ManagedTensor::<f32>::with_deleter(tensor(), move |t| {
+            assert_eq!(t.as_slice::<f32>().unwrap(), &[1., 2., 3.]);
+            counter.fetch_add(1, Ordering::SeqCst);
+        });
+        drop(mt);
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+        let counter = calls.clone();
+        let mt = ManagedTensorVersioned::<f32>::with_deleter(tensor(), move |_| {
+            counter.fetch_add(1, Ordering::SeqCst);
+        });
+        let ptr = mt.into_raw();
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+        unsafe { (*ptr).deleter.unwrap()(ptr) };
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn versioned_managed_tensor() {
+        let mut mt = ManagedTensorVersioned::from_vec(vec![1u16, 2, 3, 4], &[1, 4]).unwrap();
+        assert!(mt.flags().is_empty());
+        mt.tensor_mut().unwrap().as_slice_mut::<u16>().unwrap()[0] = 0;
+        let ptr = mt.with_flags(TensorFlags::READ_ONLY).into_raw();
+        assert_eq!(unsafe { (*ptr).flags }, 1);
+        let mut mt = unsafe { ManagedTensorVersioned::<u16>::from_raw(ptr) }.unwrap();
+        assert!(mt.is_read_only() && !mt.is_copied());
+        assert!(matches!(mt.tensor_mut(), Err(TensorAccessError::ReadOnly)));
+        assert_eq!(mt.tensor().as_slice::<u16>().unwrap(), &[0, 2, 3, 4]);
+
+        let ptr = mt.into_raw();
+        unsafe { (*ptr).version.major += 1 };
+        assert!(unsafe { ManagedTensorVersioned::<u16>::from_raw(ptr) }.is_err());
+    }
+
+    #[test]
+    fn negative_strides() {
+        let mut data = [0u8, 1, 2, 3, 4, 5];
+        let mut shape = [2_i64, 3];
+        // the rows of a 2x3 tensor in reverse order, as exported by `arr[::-1]` in NumPy
+        let mut strides = [-3_i64, 1];
+        let t = unsafe {
+            Tensor::new(
+                data.as_mut_ptr() as *mut c_void,
+                Device::default(),
+                2,
+                DataType::u8(),
+                shape.as_mut_ptr(),
+                strides.as_mut_ptr(),
+                3,
+            )
+        };
+        assert_eq!(t.strides_i64(), Some(&[-3_i64, 1][..]));
+        assert_eq!(t.strides(), Some(vec![-3, 1]));
+        assert_eq!(t.numel().unwrap(), 6);
+        assert_eq!(t.size(), Some(6));
+        assert_eq!(t.element_offset(&[1, 2]), Some(-1));
+        assert_eq!(t.element_offset(&[2, 0]), None);
+        assert!(t.validate().is_ok());
+        assert!(matches!(
+            t.as_slice::<u8>(),
+            Err(TensorAccessError::NotContiguous)
+        ));
+
+        unsafe { *t.inner.shape = -2 };
+        assert_eq!(t.shape_i64(), Some(&[-2_i64, 3][..]));
+        assert!(matches!(
+            t.shape(),
+            Err(ValidationError::NegativeShape { dim: 0, size: -2 })
+        ));
+        assert_eq!(t.size(), None);
+    }
+
+    #[test]
+    fn contiguity() {
+        let mut data = [0f32; 6];
+        let mut shape = [2_i64, 1, 3];
+        let mut t = unsafe {
+            Tensor::new(
+                data.as_mut_ptr() as *mut c_void,
+                Device::default(),
+                3,
+                DataType::f32(),
+                shape.as_mut_ptr(),
+                ptr::null_mut(),
+                0,
+            )
+        };
+        assert_eq!(t.effective_strides().unwrap().as_ref(), &[3, 3, 1]);
+        assert!(t.is_c_contiguous() && !t.is_f_contiguous() && t.is_compact());
+
+        // column-major with an arbitrary stride for the size-1 dimension
+        let mut strides = [1_i64, 42, 2];
+        t.inner.strides = strides.as_mut_ptr();
+        assert_eq!(t.effective_strides().unwrap().as_ref(), &[1, 42, 2]);
+        assert!(!t.is_c_contiguous() && t.is_f_contiguous() && t.is_compact());
+
+        // the same with both dimensions reversed
+        unsafe { ptr::copy([-1_i64, 42, -2].as_ptr(), t.inner.strides, 3) };
+        assert!(!t.is_c_contiguous() && !t.is_f_contiguous() && t.is_compact());
+
+        unsafe { ptr::copy([2_i64, 1, 4].as_ptr(), t.inner.strides, 3) };
+        assert!(!t.is_c_contiguous() && !t.is_f_contiguous() && !t.is_compact());
+
+        unsafe { *t.inner.shape = 0 };
+        assert!(t.is_c_contiguous() && t.is_f_contiguous() && t.is_compact());
+
+        // the row-major stride of the first dimension overflows
+        unsafe { ptr::copy([1, i64::MAX, 2].as_ptr(), t.inner.shape, 3) };
+        t.inner.strides = ptr::null_mut();
+        assert!(matches!(
+            t.effective_strides(),
+            Err(ValidationError::ElementCountOverflow)
+        ));
+        assert!(!t.is_f_contiguous() && !t.is_compact());
+    }
+
+    #[test]
+    fn validation() {
+        let mut data = [0f64; 6];
+        let mut shape = [2_i64, 3];
+        let mut strides = [3_i64, 1];
+        let mut t = unsafe {
+            Tensor::new(
+                data.as_mut_ptr() as *mut c_void,
+                Device::default(),
+                2,
+                DataType::f64(),
+                shape.as_mut_ptr(),
+                strides.as_mut_ptr(),
+                0,
+            )
+        };
+        let set = |ptr: *mut i64, values: [i64; 2]| unsafe { ptr::copy(values.as_ptr(), ptr, 2) };
+        assert!(t.validate().is_ok());
+        let dlt = t.inner;
+        let view = unsafe { Tensor::from_raw_ref(&dlt) };
+        assert!(view.validate().is_ok());
+        assert_eq!(view.dtype(), DataType::f64());
+        set(t.inner.strides, [-3, 1]);
+        assert!(matches!(
+            t.validate(),
+            Err(ValidationError::StridesOutOfBounds)
+        ));
+        t.inner.byte_offset = 24;
+        assert!(t.validate().is_ok());
+        t.inner.byte_offset = 28;
+        assert!(matches!(t.validate(), Err(ValidationError::Misaligned(8))));
+        t.inner.byte_offset = 0;
+        set(t.inner.shape, [2, -3]);
+        assert!(matches!(
+            t.validate(),
+            Err(ValidationError::NegativeShape { dim: 1, size: -3 })
+        ));
+        set(t.inner.shape, [i64::MAX, 3]);
+        assert!(matches!(
+            t.validate(),
+            Err(ValidationError::ElementCountOverflow)
+        ));
+        set(t.inner.shape, [1 << 62, 1]);
+        set(t.inner.strides, [i64::MIN, 1]);
+        t.inner.dtype.lanes = u16::MAX;
+        assert!(matches!(
+            t.validate(),
+            Err(ValidationError::ByteSizeOverflow)
+        ));
+        t.inner.dtype.lanes = 1;
+        set(t.inner.shape, [2, 3]);
+        set(t.inner.strides, [3, 1]);
+        t.inner.data = ptr::null_mut();
+        assert!(matches!(t.validate(), Err(ValidationError::NullData(6))));
+        t.inner.ndim = -1;
+        assert!(matches!(
+            t.validate(),
+            Err(ValidationError::NegativeNdim(-1))
+        ));
+        t.inner.dtype.code = 42;
+        assert!(matches!(
+            t.validate(),
+            Err(ValidationError::UnsupportedDataTypeCode(_))
+        ));
+        t.inner.ndim = 2;
+        t.inner.dtype.code = 2;
+        t.inner.device.device_type = 42;
+        assert!(matches!(t.validate(), Err(ValidationError::NullData(6))));
+        t.inner.device.device_id = -1;
+        assert!(matches!(
+            t.validate(),
+            Err(ValidationError::UnsupportedDevice(_))
+        ));
+    }
+
+    #[test]
+    fn slice_access() {
+        let mut mt = ManagedTensor::from_vec(vec![true, false, true, true], &[2, 2]).unwrap();
+        mt.tensor_mut().as_slice_mut::<bool>().unwrap()[1] = true;
+        assert_eq!(mt.tensor().as_slice::<bool>().unwrap(), &[true; 4]);
+
+        let mut bytes = vec![0u8, 1, 2, 3];
+        let mut shape = [2_i64, 2];
+        let mut strides = [1_i64, 2];
+        let mut t = unsafe {
+            Tensor::new(
+                bytes.as_mut_ptr() as *mut c_void,
+                Device::default(),
+                2,
+                DataType::bool(),
+                shape.as_mut_ptr(),
+                ptr::null_mut(),
+                0,
+            )
+        };
+        assert!(matches!(
+            t.as_slice::<bool>(),
+            Err(TensorAccessError::InvalidValue)
+        ));
+        assert!(matches!(
+            t.as_slice::<u8>(),
+            Err(TensorAccessError::DataTypeMismatch { .. })
+        ));
+        t.inner.dtype = DataType::u8().into();
+        assert_eq!(t.as_slice::<u8>().unwrap(), &[0, 1, 2, 3]);
+        t.inner.strides = strides.as_mut_ptr();
+        assert!(matches!(
+            t.as_slice::<u8>(),
+            Err(TensorAccessError::NotContiguous)
+        ));
+        t.inner.device = Device::cuda(0).into();
+        assert!(matches!(
+            t.as_slice::<u8>(),
+            Err(TensorAccessError::NotOnHost(_))
+        ));
+
+        let mut t = unsafe {
+            Tensor::new(
+                bytes.as_mut_ptr() as *mut c_void,
+                Device::default(),
+                1,
+                DataType::u16(),
+                shape.as_mut_ptr(),
+                ptr::null_mut(),
+                1,
+            )
+        };
+        assert!(matches!(
+            t.as_slice_mut::<u16>(),
+            Err(TensorAccessError::Misaligned(2))
+        ));
+        assert!(ManagedTensor::from_vec(vec![0f32; 0], &[usize::MAX, 0]).is_err());
     }
 }
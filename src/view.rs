@@ -0,0 +1,499 @@
+//! Tensors borrowing their data which keep their shape and strides inline.
+//!
+//! Unlike [`Tensor`], whose shape and strides are raw pointers to memory owned by someone else, a
+//! [`TensorView`] or [`TensorViewMut`] stores its shape and strides itself, without allocating for up
+//! to four dimensions, and borrows its data for `'a`. The `DLTensor` it exposes points into the view
+//! and is valid as long as the view is borrowed. Views of any layout are built with
+//! [`Tensor::builder`].
+//!
+//! ## Example
+//!
+//! ```
+//! use dlpackrs::{DataType, TensorView, TensorViewMut};
+//!
+//! let data = [1f32, 2., 3., 4., 5., 6.];
+//! let view = TensorView::new(&data, &[2, 3]).unwrap();
+//! assert_eq!(view.tensor().dtype(), DataType::f32());
+//! assert_eq!(view.tensor().strides(), Some(vec![3, 1]));
+//! assert_eq!(view.as_dltensor().ndim, 2);
+//!
+//! let mut data = [0u8; 4];
+//! let mut view = TensorViewMut::new(&mut data, &[4]).unwrap();
+//! view.tensor_mut().as_slice_mut::<u8>().unwrap()[1] = 1;
+//! drop(view);
+//! assert_eq!(data, [0, 1, 0, 0]);
+//! ```
+
+use std::{
+    cell::UnsafeCell,
+    convert::TryFrom,
+    fmt::{self, Debug, Formatter},
+    marker::PhantomData,
+    mem,
+    os::raw::c_void,
+};
+
+use smallvec::SmallVec;
+
+use crate::{
+    datatype::{DataType, Element},
+    device::Device,
+    errors::{ShapeMismatchError, ValidationError},
+    ffi::DLTensor,
+    tensor::{compact_layout, row_major_strides, Tensor, TensorMut},
+};
+
+/// Shape or strides, inline for up to four dimensions.
+type Dims = SmallVec<[i64; 4]>;
+
+/// A DLTensor along with the shape and strides it points to.
+///
+/// Since the shape and strides move along with the view, the pointers of the DLTensor are updated
+/// before it is handed out. They are only stale after a move or a mutable borrow, when no
+/// reference to the DLTensor is alive, and the DLTensor holds raw pointers, so the view is neither
+/// `Send` nor `Sync` and cannot be updated from two threads at once.
+struct Parts {
+    inner: UnsafeCell<DLTensor>,
+    shape: Dims,
+    strides: Dims,
+}
+
+impl Parts {
+    fn new(
+        data: *mut c_void,
+        device: Device,
+        dtype: DataType,
+        shape: Dims,
+        strides: Dims,
+        byte_offset: u64,
+    ) -> Self {
+        let inner = DLTensor {
+            data,
+            device: device.into(),
+            ndim: shape.len() as i32,
+            dtype: dtype.into(),
+            shape: std::ptr::null_mut(),
+            strides: std::ptr::null_mut(),
+            byte_offset,
+        };
+        Parts {
+            inner: UnsafeCell::new(inner),
+            shape,
+            strides,
+        }
+    }
+
+    fn tensor<'a>(&self) -> &Tensor<'a> {
+        let inner = self.inner.get();
+        let shape = self.shape.as_ptr() as *mut i64;
+        let strides = self.strides.as_ptr() as *mut i64;
+        let ndim = self.shape.len() as i32;
+        unsafe {
+            if (*inner).shape != shape || (*inner).strides != strides || (*inner).ndim != ndim {
+                (*inner).shape = shape;
+                (*inner).strides = strides;
+                (*inner).ndim = ndim;
+            }
+            &*(inner as *const Tensor<'a>)
+        }
+    }
+
+    fn tensor_mut<'a>(&mut self) -> &mut Tensor<'a> {
+        let inner = self.inner.get_mut();
+        inner.shape = self.shape.as_mut_ptr();
+        inner.strides = self.strides.as_mut_ptr();
+        inner.ndim = self.shape.len() as i32;
+        unsafe { &mut *(inner as *mut DLTensor as *mut Tensor<'a>) }
+    }
+}
+
+/// Returns the shape and compact row-major strides of a slice of `len` elements.
+fn compact_dims(shape: &[usize], len: usize) -> Result<(Dims, Dims), ShapeMismatchError> {
+    let (shape, strides) = compact_layout(shape, len)?;
+    Ok((Dims::from_vec(shape), Dims::from_vec(strides)))
+}
+
+/// A read-only Tensor on cpu borrowing its data for `'a`, which stores its shape and strides.
+pub struct TensorView<'a> {
+    parts: Parts,
+    _marker: PhantomData<&'a ()>,
+}
+
+impl<'a> TensorView<'a> {
+    /// Creates a compact row-major view of the slice, failing unless the shape holds exactly as
+    /// many elements as the slice.
+    pub fn new<T: Element>(data: &'a [T], shape: &[usize]) -> Result<Self, ShapeMismatchError> {
+        let (shape, strides) = compact_dims(shape, data.len())?;
+        Ok(TensorView {
+            parts: Parts::new(
+                data.as_ptr() as *mut c_void,
+                Device::default(),
+                T::DTYPE,
+                shape,
+                strides,
+                0,
+            ),
+            _marker: PhantomData,
+        })
+    }
+
+    /// Returns the underlying Tensor, which can only be read through.
+    pub fn tensor(&self) -> &Tensor<'a> {
+        self.parts.tensor()
+    }
+
+    /// Returns the underlying DLTensor, whose shape and strides point into the view.
+    pub fn as_dltensor(&self) -> &DLTensor {
+        self.tensor().as_dltensor()
+    }
+}
+
+impl Debug for TensorView<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TensorView")
+            .field("tensor", self.tensor())
+            .finish()
+    }
+}
+
+/// A Tensor on cpu borrowing its data mutably for `'a`, which stores its shape and strides.
+pub struct TensorViewMut<'a> {
+    parts: Parts,
+    _marker: PhantomData<&'a mut ()>,
+}
+
+impl<'a> TensorViewMut<'a> {
+    /// Same as [`TensorView::new`] for a mutable slice.
+    pub fn new<T: Element>(data: &'a mut [T], shape: &[usize]) -> Result<Self, ShapeMismatchError> {
+        let (shape, strides) = compact_dims(shape, data.len())?;
+        Ok(TensorViewMut {
+            parts: Parts::new(
+                data.as_mut_ptr() as *mut c_void,
+                Device::default(),
+                T::DTYPE,
+                shape,
+                strides,
+                0,
+            ),
+            _marker: PhantomData,
+        })
+    }
+
+    /// Returns the underlying Tensor.
+    pub fn tensor(&self) -> &Tensor<'a> {
+        self.parts.tensor()
+    }
+
+    /// Returns the underlying Tensor mutably. Its shape and strides pointers and number of
+    /// dimensions are restored the next time the Tensor is borrowed.
+    pub fn tensor_mut(&mut self) -> TensorMut<'_, 'a> {
+        TensorMut(self.parts.tensor_mut())
+    }
+
+    /// Returns the underlying DLTensor, whose shape and strides point into the view.
+    pub fn as_dltensor(&self) -> &DLTensor {
+        self.tensor().as_dltensor()
+    }
+
+    /// Returns the underlying DLTensor mutably, e.g. to hand it out to a consumer writing to it.
+    ///
+    /// # Safety
+    ///
+    /// The DLTensor must keep describing entries within the borrowed slice, see [`Tensor::new`].
+    pub unsafe fn as_dltensor_mut(&mut self) -> &mut DLTensor {
+        self.parts.tensor_mut().as_dltensor_mut()
+    }
+}
+
+impl Debug for TensorViewMut<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TensorViewMut")
+            .field("tensor", self.tensor())
+            .finish()
+    }
+}
+
+impl<'a> Tensor<'a> {
+    /// Returns a builder of a [`TensorViewMut`] borrowing a mutable slice.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use dlpackrs::{errors::ValidationError, Device, Tensor};
+    ///
+    /// let mut data = [1f32, 2., 3., 4., 5., 6.];
+    /// // the transpose of the 2x3 matrix
+    /// let view = Tensor::builder()
+    ///     .data(&mut data)
+    ///     .shape([3, 2])
+    ///     .strides([1, 3])
+    ///     .device(Device::cpu(0))
+    ///     .build()?;
+    /// assert!(view.tensor().is_f_contiguous());
+    ///
+    /// let too_large = Tensor::builder().data(&mut data).shape([3, 3]).build();
+    /// assert!(matches!(too_large, Err(ValidationError::DataOutOfBounds(24))));
+    /// # Ok::<(), ValidationError>(())
+    /// ```
+    pub fn builder() -> TensorBuilder<'a> {
+        TensorBuilder::new()
+    }
+}
+
+/// Builder of a [`TensorViewMut`], see [`Tensor::builder`].
+///
+/// The data type defaults to the one of the slice elements, the shape to the one-dimensional shape
+/// of the whole slice and the strides to the compact row-major ones. The data is on cpu unless
+/// another device is given, e.g. for host memory visible to a device. The data can only be
+/// reinterpreted as another data type if every byte pattern is a valid slice element, see
+/// [`Element::ALL_BITS_VALID`], since the view could otherwise write invalid elements to it.
+#[derive(Debug)]
+pub struct TensorBuilder<'a> {
+    data: *mut c_void,
+    len: usize,
+    source: Option<(DataType, bool)>,
+    dtype: Option<DataType>,
+    shape: Option<Vec<usize>>,
+    strides: Option<Vec<isize>>,
+    device: Device,
+    byte_offset: u64,
+    _marker: PhantomData<&'a mut ()>,
+}
+
+impl<'a> Default for TensorBuilder<'a> {
+    fn default() -> Self {
+        TensorBuilder::new()
+    }
+}
+
+impl<'a> TensorBuilder<'a> {
+    pub fn new() -> Self {
+        TensorBuilder {
+            data: std::ptr::null_mut(),
+            len: 0,
+            source: None,
+            dtype: None,
+            shape: None,
+            strides: None,
+            device: Device::default(),
+            byte_offset: 0,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Sets the data, whose element type is the data type unless another one is given.
+    pub fn data<T: Element>(mut self, data: &'a mut [T]) -> Self {
+        self.data = data.as_mut_ptr() as *mut c_void;
+        self.len = mem::size_of_val(data);
+        self.source = Some((T::DTYPE, T::ALL_BITS_VALID));
+        self.dtype = self.dtype.or(Some(T::DTYPE));
+        self
+    }
+
+    /// Sets the shape.
+    pub fn shape(mut self, shape: impl AsRef<[usize]>) -> Self {
+        self.shape = Some(shape.as_ref().to_vec());
+        self
+    }
+
+    /// Sets the strides (in number of elements), possibly negative.
+    pub fn strides(mut self, strides: impl AsRef<[isize]>) -> Self {
+        self.strides = Some(strides.as_ref().to_vec());
+        self
+    }
+
+    /// Sets the data type to the one of `T`, reinterpreting the data, see [`TensorBuilder`].
+    pub fn dtype<T: Element>(self) -> Self {
+        self.data_type(T::DTYPE)
+    }
+
+    /// Sets the data type, e.g. one without an [`Element`] like the packed or float8 ones.
+    pub fn data_type(mut self, dtype: DataType) -> Self {
+        self.dtype = Some(dtype);
+        self
+    }
+
+    /// Sets the device.
+    pub fn device(mut self, device: Device) -> Self {
+        self.device = device;
+        self
+    }
+
+    /// Sets the offset (in bytes) of the first entry from the start of the data.
+    pub fn byte_offset(mut self, byte_offset: u64) -> Self {
+        self.byte_offset = byte_offset;
+        self
+    }
+
+    /// Builds the view, failing if it does not pass [`Tensor::validate`] or any of its entries
+    /// lies outside of the data.
+    pub fn build(self) -> Result<TensorViewMut<'a>, ValidationError> {
+        let dtype = self.dtype.unwrap_or(DataType::new(0, 0, 0));
+        let item_bits = dtype.bits as usize * dtype.lanes as usize;
+        if item_bits == 0 {
+            return Err(ValidationError::InvalidDataType(dtype));
+        }
+        if let Some((source, false)) = self.source {
+            let source_bytes = source.bits as u64 / 8 * source.lanes as u64;
+            if dtype != source || self.byte_offset % source_bytes != 0 {
+                return Err(ValidationError::InvalidReinterpretation {
+                    from: source,
+                    to: dtype,
+                });
+            }
+        }
+        let shape = self
+            .shape
+            .unwrap_or_else(|| vec![self.len.saturating_mul(8) / item_bits]);
+        let shape = shape
+            .iter()
+            .map(|&size| i64::try_from(size))
+            .collect::<Result<Dims, _>>()
+            .map_err(|_| ValidationError::ElementCountOverflow)?;
+        let strides = match self.strides {
+            Some(strides) if strides.len() != shape.len() => {
+                return Err(ValidationError::StridesMismatch {
+                    ndim: shape.len(),
+                    strides: strides.len(),
+                })
+            }
+            Some(strides) => strides.iter().map(|&stride| stride as i64).collect(),
+            None => Dims::from_vec(row_major_strides(&shape)?),
+        };
+        let view = TensorViewMut {
+            parts: Parts::new(
+                self.data,
+                self.device,
+                dtype,
+                shape,
+                strides,
+                self.byte_offset,
+            ),
+            _marker: PhantomData,
+        };
+        let t = view.tensor();
+        t.validate()?;
+        if t.numel()? > 0 {
+            let (_, high) = t.offset_range()?;
+            let end = self.byte_offset as i128 * 8 + (high + 1) * item_bits as i128;
+            if end > self.len as i128 * 8 {
+                return Err(ValidationError::DataOutOfBounds(self.len));
+            }
+        }
+        Ok(view)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn views_own_their_dims() {
+        let data = (0..24).collect::<Vec<i32>>();
+        let views = (0..2)
+            .map(|_| TensorView::new(&data, &[2, 3, 4]).unwrap())
+            .collect::<Vec<_>>();
+        // the views have moved into the vector
+        for view in &views {
+            let t = view.tensor();
+            assert_eq!(t.shape_i64(), Some(&[2, 3, 4][..]));
+            assert_eq!(t.strides_i64(), Some(&[12, 4, 1][..]));
+            assert!(t.validate().is_ok());
+            assert_eq!(t.as_slice::<i32>().unwrap(), &data[..]);
+        }
+        let view = TensorView::new(&data, &[1, 1, 2, 3, 4]).unwrap();
+        assert_eq!(view.as_dltensor().ndim, 5);
+        assert_eq!(view.tensor().numel().unwrap(), 24);
+        assert!(TensorView::new(&data, &[5, 5]).is_err());
+
+        let mut data = [1f64, 2., 3., 4.];
+        let mut view = TensorViewMut::new(&mut data, &[2, 2]).unwrap();
+        unsafe { view.as_dltensor_mut().ndim = 1 };
+        let mut moved = Box::new(view);
+        let mut t = moved.tensor_mut();
+        assert_eq!(t.shape().unwrap(), vec![2, 2]);
+        t.as_slice_mut::<f64>().unwrap()[3] = 0.;
+        drop(moved);
+        assert_eq!(data, [1., 2., 3., 0.]);
+    }
+
+    #[test]
+    fn builder() {
+        let mut data = [0u8; 12];
+        let view = Tensor::builder()
+            .data(&mut data)
+            .dtype::<u16>()
+            .shape([2, 2])
+            .strides([-2, 1])
+            .byte_offset(4)
+            .build()
+            .unwrap();
+        let t = view.tensor();
+        assert_eq!(t.dtype(), DataType::u16());
+        assert_eq!(t.element_offset(&[1, 1]), Some(-1));
+
+        let build = |shape: &[usize], strides: &[isize], byte_offset| {
+            let mut data = [0u16; 6];
+            Tensor::builder()
+                .data(&mut data)
+                .shape(shape)
+                .strides(strides)
+                .byte_offset(byte_offset)
+                .build()
+                .map(|view| view.tensor().numel().unwrap())
+        };
+        assert_eq!(build(&[2, 3], &[3, 1], 0).unwrap(), 6);
+        assert_eq!(build(&[2, 0], &[9, 9], 100).unwrap(), 0);
+        assert!(matches!(
+            build(&[2, 3], &[3, 1], 2),
+            Err(ValidationError::DataOutOfBounds(12))
+        ));
+        assert!(matches!(
+            build(&[2, 3], &[3], 0),
+            Err(ValidationError::StridesMismatch {
+                ndim: 2,
+                strides: 1
+            })
+        ));
+        assert!(matches!(
+            build(&[2, 3], &[-3, 1], 0),
+            Err(ValidationError::StridesOutOfBounds)
+        ));
+        assert!(matches!(
+            build(&[3], &[1], 1),
+            Err(ValidationError::Misaligned(2))
+        ));
+
+        let mut data = [0b0010_0001u8];
+        let view = Tensor::builder()
+            .data(&mut data)
+            .data_type(DataType::new(1, 4, 1))
+            .build()
+            .unwrap();
+        assert_eq!(view.tensor().unpack::<u8>().unwrap(), vec![1, 2]);
+        assert!(matches!(
+            Tensor::builder().build(),
+            Err(ValidationError::InvalidDataType(_))
+        ));
+
+        let mut flags = [true, false];
+        assert!(matches!(
+            Tensor::builder().data(&mut flags).dtype::<u8>().build(),
+            Err(ValidationError::InvalidReinterpretation { .. })
+        ));
+        assert!(matches!(
+            Tensor::builder()
+                .data(&mut flags)
+                .data_type(DataType::new(1, 4, 1))
+                .build(),
+            Err(ValidationError::InvalidReinterpretation { .. })
+        ));
+        let view = Tensor::builder()
+            .data(&mut flags)
+            .byte_offset(1)
+            .shape([1])
+            .build();
+        assert_eq!(view.unwrap().tensor().as_slice::<bool>().unwrap(), [false]);
+    }
+}